@@ -0,0 +1,133 @@
+use crate::pretty::{pretty, Style};
+use crate::sat;
+use crate::{negate, Expr};
+
+// ============================================================================
+// PREMISE ANALYSIS
+// ============================================================================
+
+/// Findings about a premise set, with premises referred to by 0-based index
+pub struct Analysis {
+    pub consistent: bool,
+    pub inconsistent_core: Vec<usize>,   // Minimal jointly inconsistent subset (if inconsistent)
+    pub redundant: Vec<usize>,           // Premises entailed by the remaining ones
+    pub needed: Option<(Expr, Vec<usize>)>, // Conclusion that follows and a minimal subset for it
+    pub quantified: bool,                // Quantified or modal formulas were treated as atoms
+    pub detailed: bool,                  // The per-premise checks above were run
+}
+
+/// Above this many premises only joint consistency is checked unless --analyze is
+/// given, since the per-premise checks make a SAT call for every premise
+pub const DETAIL_LIMIT: usize = 50;
+
+/// True when `e` contains a quantifier or a modal operator
+pub(crate) fn has_quantifier(e: &Expr) -> bool {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) => false,
+        Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => true,
+        Expr::Not(a) => has_quantifier(a),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => has_quantifier(a) || has_quantifier(b),
+    }
+}
+
+fn pick(premises: &[Expr], idx: &[usize]) -> Vec<Expr> {
+    idx.iter().map(|&i| premises[i].clone()).collect()
+}
+
+/// Shrink `keep` to a minimal subset for which `holds` is still true
+/// (deletion-based: drop each premise in turn and keep it out if `holds` survives)
+fn minimal_subset(premises: &[Expr], mut keep: Vec<usize>, holds: impl Fn(&[Expr]) -> bool) -> Vec<usize> {
+    let mut i = 0;
+    while i < keep.len() {
+        let mut without = keep.clone();
+        without.remove(i);
+        if holds(&pick(premises, &without)) { keep = without; } else { i += 1; }
+    }
+    keep
+}
+
+/// Check the premises with the SAT solver before any proof is attempted:
+///   - Are they jointly inconsistent (so that anything follows)?
+///   - Which premises are redundant because the others entail them?
+///   - Which minimal subset of premises does the deduction (or its negation) need?
+///
+/// Only the first question is answered unless `detailed`, as the others take O(n) SAT calls.
+pub fn analyze(premises: &[Expr], target: &Expr, detailed: bool) -> Analysis {
+    let all: Vec<usize> = (0..premises.len()).collect();
+    let consistent = sat::model(premises).is_some();
+    let quantified = premises.iter().chain(std::iter::once(target)).any(has_quantifier);
+    if !detailed {
+        return Analysis { consistent, inconsistent_core: Vec::new(), redundant: Vec::new(), needed: None, quantified, detailed };
+    }
+
+    let inconsistent_core = if consistent {
+        Vec::new()
+    } else {
+        minimal_subset(premises, all.clone(), |ps| sat::model(ps).is_none())
+    };
+
+    let redundant = (0..premises.len())
+        .filter(|&i| {
+            let others: Vec<usize> = all.iter().copied().filter(|&j| j != i).collect();
+            sat::entails(&pick(premises, &others), &premises[i])
+        })
+        .collect();
+
+    let needed = [target.clone(), negate(target)].into_iter()
+        .find(|goal| sat::entails(premises, goal))
+        .map(|goal| {
+            let subset = minimal_subset(premises, all.clone(), |ps| sat::entails(ps, &goal));
+            (goal, subset)
+        });
+
+    Analysis { consistent, inconsistent_core, redundant, needed, quantified, detailed }
+}
+
+fn numbers(idx: &[usize]) -> String {
+    let n: Vec<String> = idx.iter().map(|i| (i + 1).to_string()).collect();
+    n.join(", ")
+}
+
+/// Print the analysis in the same register as the proof output
+pub fn print_analysis(premises: &[Expr], a: &Analysis) {
+    println!("Premise Analysis:");
+    if a.consistent {
+        println!("  Consistency: consistent");
+    } else {
+        println!("  Consistency: INCONSISTENT - every conclusion follows, so any verdict below is vacuous");
+        if a.detailed {
+            println!("  Minimal inconsistent premises: {}", numbers(&a.inconsistent_core));
+        }
+    }
+
+    if !a.detailed {
+        println!("  Redundant and needed premises: not checked for {} premises (use --analyze)", premises.len());
+        if a.quantified {
+            println!("  (quantified and modal formulas were treated as atoms for this analysis)");
+        }
+        println!();
+        return;
+    }
+
+    if a.redundant.is_empty() {
+        println!("  Redundant premises: none");
+    } else {
+        for &i in &a.redundant {
+            println!("  Redundant premise {}: {} (entailed by the others)", i + 1, pretty(&premises[i], Style::Ascii));
+        }
+        if a.redundant.len() > 1 {
+            println!("  (each is redundant on its own; dropping one may make another necessary)");
+        }
+    }
+
+    match &a.needed {
+        Some((goal, subset)) if subset.is_empty() => println!("  Premises needed for {}: none (it is a tautology)", pretty(goal, Style::Ascii)),
+        Some((goal, subset)) => println!("  Premises needed for {}: {}", pretty(goal, Style::Ascii), numbers(subset)),
+        None => println!("  Premises needed: neither the deduction nor its negation follows"),
+    }
+
+    if a.quantified {
+        println!("  (quantified and modal formulas were treated as atoms for this analysis)");
+    }
+    println!();
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use std::time::Instant;
+
+use crate::{modal, negate, prove, resolution, sat, tableau, Engine, Expr, Options, Parser};
+
+// ============================================================================
+// PROBLEM FILES
+// ============================================================================
+
+/// One argument with the verdict it should get
+/// File format (blank lines and lines starting with '#' are ignored):
+///   [name]            starts a problem
+///   p > q             premises, one per line
+///   R q               the deduction
+///   expect Valid      Valid, Invalid or Unknown (Insufficient Information)
+pub struct Problem {
+    pub name: String,
+    pub line: usize,          // Line of the [name] header, for messages
+    pub premises: Vec<Expr>,
+    pub goal: Expr,
+    pub expected: &'static str,
+}
+
+/// Map a verdict written in a problem file to the solver's wording
+fn verdict_named(s: &str) -> Option<&'static str> {
+    match s.to_lowercase().as_str() {
+        "valid" => Some("Valid"),
+        "invalid" => Some("Invalid"),
+        "unknown" | "insufficient" | "insufficient information" => Some("Insufficient Information"),
+        _ => None,
+    }
+}
+
+/// Parse a problem file; errors carry the line number
+pub fn parse_problems(text: &str) -> Result<Vec<Problem>, String> {
+    struct Partial { name: String, line: usize, premises: Vec<Expr>, goal: Option<Expr>, expected: Option<&'static str> }
+
+    fn finish(p: Partial) -> Result<Problem, String> {
+        let goal = p.goal.ok_or(format!("line {}: problem '{}' has no 'R' line", p.line, p.name))?;
+        let expected = p.expected.ok_or(format!("line {}: problem '{}' has no 'expect' line", p.line, p.name))?;
+        Ok(Problem { name: p.name, line: p.line, premises: p.premises, goal, expected })
+    }
+
+    let mut problems = Vec::new();
+    let mut current: Option<Partial> = None;
+    for (n, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let at = |msg: String| format!("line {}: {}", n + 1, msg);
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some(p) = current.take() { problems.push(finish(p)?); }
+            current = Some(Partial { name: name.trim().to_string(), line: n + 1, premises: Vec::new(), goal: None, expected: None });
+            continue;
+        }
+        let p = current.as_mut().ok_or_else(|| at("expected a [name] header first".to_string()))?;
+        if let Some(v) = line.strip_prefix("expect ") {
+            p.expected = Some(verdict_named(v.trim()).ok_or_else(|| at(format!("unknown verdict: {}", v.trim())))?);
+        } else if let Some(goal) = line.strip_prefix('R') {
+            p.goal = Some(Parser::new(goal.trim()).parse_all().map_err(at)?);
+        } else {
+            p.premises.push(Parser::new(line).parse_all().map_err(at)?);
+        }
+    }
+    if let Some(p) = current { problems.push(finish(p)?); }
+    Ok(problems)
+}
+
+// ============================================================================
+// RUNNER
+// ============================================================================
+
+/// Verdict of the selected engine without printing anything
+fn verdict(p: &Problem, opts: &Options) -> &'static str {
+    match opts.engine {
+        Engine::Sat => {
+            if sat::entails(&p.premises, &p.goal) { "Valid" }
+            else if sat::entails(&p.premises, &negate(&p.goal)) { "Invalid" }
+            else { "Insufficient Information" }
+        }
+        Engine::Resolution => resolution::verdict(&p.premises, &p.goal),
+        Engine::Tableau => tableau::verdict(&p.premises, &p.goal),
+        Engine::Modal => modal::decide(&p.premises, &p.goal, opts.system).0,
+        Engine::Forward => prove(p.premises.clone(), &p.goal, &opts.limits, &opts.lemmas, false).verdict,
+    }
+}
+
+/// Solve every problem in the file and report pass/fail with timings
+/// Returns true when every problem got its expected verdict.
+pub fn run(path: &str, opts: &Options) -> bool {
+    let problems = match fs::read_to_string(path) {
+        Ok(text) => match parse_problems(&text) {
+            Ok(p) => p,
+            Err(e) => { println!("{}: {}", path, e); return false; }
+        },
+        Err(e) => { println!("Could not read {}: {}", path, e); return false; }
+    };
+    let width = problems.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let start = Instant::now();
+    let mut failed = Vec::new();
+    for p in &problems {
+        let t = Instant::now();
+        let got = verdict(p, opts);
+        let ms = t.elapsed().as_secs_f64() * 1000.0;
+        if got == p.expected {
+            println!("PASS  {:<width$}  {:<24}  {:>9.3} ms", p.name, got, ms, width = width);
+        } else {
+            println!("FAIL  {:<width$}  {:<24}  {:>9.3} ms  (expected {}, line {})", p.name, got, ms, p.expected, p.line, width = width);
+            failed.push(&p.name);
+        }
+    }
+
+    println!("\n{} problems: {} passed, {} failed ({:.3} ms)",
+        problems.len(), problems.len() - failed.len(), failed.len(), start.elapsed().as_secs_f64() * 1000.0);
+    failed.is_empty()
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::Expr;
+
+// ============================================================================
+// CLAUSAL FORM
+// ============================================================================
+
+/// A literal in DIMACS convention: variable index (1-based), negative if negated
+pub type Lit = i32;
+
+/// A disjunction of literals
+pub type Clause = Vec<Lit>;
+
+/// A formula in conjunctive normal form together with the names of its variables
+#[derive(Clone, Debug, Default)]
+pub struct Cnf {
+    pub num_vars: usize,              // Highest variable index in use
+    pub clauses: Vec<Clause>,         // Conjunction of clauses
+    pub names: HashMap<usize, String>, // Variable index -> original atom name
+}
+
+impl Cnf {
+    /// Allocate a fresh variable and return its index
+    fn fresh(&mut self) -> usize {
+        self.num_vars += 1;
+        self.num_vars
+    }
+}
+
+// ============================================================================
+// TSEITIN TRANSFORMATION
+// ============================================================================
+
+/// Convert a set of expressions (all asserted true) into an equisatisfiable CNF
+/// Each compound subformula gets a fresh variable whose defining clauses make it
+/// equivalent to the subformula, so the output grows linearly with the input.
+/// Negation never needs a fresh variable: it just flips the literal.
+pub fn tseitin(exprs: &[Expr]) -> Cnf {
+    let mut cnf = Cnf::default();
+    let mut atoms: HashMap<String, usize> = HashMap::new();
+    let mut cache: HashMap<Expr, Lit> = HashMap::new();
+
+    for e in exprs {
+        let root = encode(e, &mut cnf, &mut atoms, &mut cache);
+        cnf.clauses.push(vec![root]);
+    }
+    cnf
+}
+
+/// Return a literal equivalent to `e`, emitting its defining clauses on the way
+fn encode(e: &Expr, cnf: &mut Cnf, atoms: &mut HashMap<String, usize>, cache: &mut HashMap<Expr, Lit>) -> Lit {
+    if let Some(&lit) = cache.get(e) { return lit; }
+
+    let lit = match e {
+        // Predicates, quantified and modal formulas are opaque atoms at this level
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+            let name = e.to_string();
+            let v = match atoms.get(&name) {
+                Some(&v) => v,
+                None => {
+                    let v = cnf.fresh();
+                    atoms.insert(name.clone(), v);
+                    cnf.names.insert(v, name);
+                    v
+                }
+            };
+            v as Lit
+        }
+        Expr::Not(a) => -encode(a, cnf, atoms, cache),
+        Expr::And(a, b) => {
+            let (la, lb) = (encode(a, cnf, atoms, cache), encode(b, cnf, atoms, cache));
+            let x = cnf.fresh() as Lit;
+            // x <-> (a * b)
+            cnf.clauses.push(vec![-x, la]);
+            cnf.clauses.push(vec![-x, lb]);
+            cnf.clauses.push(vec![x, -la, -lb]);
+            x
+        }
+        Expr::Or(a, b) => {
+            let (la, lb) = (encode(a, cnf, atoms, cache), encode(b, cnf, atoms, cache));
+            let x = cnf.fresh() as Lit;
+            // x <-> (a + b)
+            cnf.clauses.push(vec![-x, la, lb]);
+            cnf.clauses.push(vec![x, -la]);
+            cnf.clauses.push(vec![x, -lb]);
+            x
+        }
+        Expr::Implies(a, b) => {
+            let (la, lb) = (encode(a, cnf, atoms, cache), encode(b, cnf, atoms, cache));
+            let x = cnf.fresh() as Lit;
+            // x <-> (a > b)
+            cnf.clauses.push(vec![-x, -la, lb]);
+            cnf.clauses.push(vec![x, la]);
+            cnf.clauses.push(vec![x, -lb]);
+            x
+        }
+    };
+
+    cache.insert(e.clone(), lit);
+    lit
+}
+
+// ============================================================================
+// DIMACS I/O
+// ============================================================================
+
+/// Parse a DIMACS CNF file
+/// Comment lines start with 'c'; a comment of the form "c var <n> <name>" records
+/// the atom name of variable n (as written by `write_dimacs`). A line starting with
+/// '%' ends the clause section, as in the SATLIB benchmark files.
+pub fn read_dimacs(text: &str) -> Result<Cnf, String> {
+    let mut cnf = Cnf::default();
+    let mut declared: Option<(usize, usize)> = None;
+    let mut current: Clause = Vec::new();
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() { continue; }
+
+        if let Some(comment) = line.strip_prefix('c') {
+            // The name is the rest of the line: predicates like P(a, b) contain spaces
+            let parts: Vec<&str> = comment.trim_start().splitn(3, ' ').collect();
+            if let ["var", idx, name] = parts.as_slice()
+                && let Ok(v) = idx.parse::<usize>() {
+                cnf.names.insert(v, name.trim().to_string());
+            }
+            continue;
+        }
+        if line.starts_with('%') { break; }
+
+        if let Some(header) = line.strip_prefix('p') {
+            let parts: Vec<&str> = header.split_whitespace().collect();
+            match parts.as_slice() {
+                ["cnf", v, c] => {
+                    let v = v.parse().map_err(|_| format!("Line {}: bad variable count", n + 1))?;
+                    let c = c.parse().map_err(|_| format!("Line {}: bad clause count", n + 1))?;
+                    declared = Some((v, c));
+                }
+                _ => return Err(format!("Line {}: expected 'p cnf <vars> <clauses>'", n + 1)),
+            }
+            continue;
+        }
+
+        let (max_var, _) = declared.ok_or(format!("Line {}: clause before 'p cnf' header", n + 1))?;
+        for tok in line.split_whitespace() {
+            let lit: Lit = tok.parse().map_err(|_| format!("Line {}: bad literal '{}'", n + 1, tok))?;
+            if lit == 0 {
+                cnf.clauses.push(std::mem::take(&mut current));
+            } else {
+                if lit.unsigned_abs() as usize > max_var {
+                    return Err(format!("Line {}: variable {} exceeds declared count {}", n + 1, lit.unsigned_abs(), max_var));
+                }
+                current.push(lit);
+            }
+        }
+    }
+
+    // Tolerate a missing terminating 0 on the last clause
+    if !current.is_empty() { cnf.clauses.push(current); }
+
+    let (num_vars, _) = declared.ok_or("Missing 'p cnf' header".to_string())?;
+    cnf.num_vars = num_vars;
+    Ok(cnf)
+}
+
+/// Serialize a CNF in DIMACS format, recording atom names as comments
+pub fn write_dimacs(cnf: &Cnf) -> String {
+    let mut out = String::new();
+    let mut named: Vec<_> = cnf.names.iter().collect();
+    named.sort();
+    for (v, name) in named {
+        let _ = writeln!(out, "c var {} {}", v, name);
+    }
+    let _ = writeln!(out, "p cnf {} {}", cnf.num_vars, cnf.clauses.len());
+    for clause in &cnf.clauses {
+        for lit in clause { let _ = write!(out, "{} ", lit); }
+        let _ = writeln!(out, "0");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn dimacs_round_trip_keeps_names_with_spaces() {
+        let exprs: Vec<Expr> = ["P(a, b) > Q(b)", "P(a, b)", "~Q(b) + r"].iter()
+            .map(|t| Parser::new(t).parse_all().unwrap())
+            .collect();
+        let cnf = tseitin(&exprs);
+        assert!(cnf.names.values().any(|n| n.contains(' ')), "{:?}", cnf.names);
+
+        let back = read_dimacs(&write_dimacs(&cnf)).unwrap();
+        assert_eq!(back.num_vars, cnf.num_vars);
+        assert_eq!(back.clauses, cnf.clauses);
+        assert_eq!(back.names, cnf.names);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::pretty::{pretty, Style};
+use crate::{Expr, Parser};
+
+// ============================================================================
+// DICTIONARY
+// ============================================================================
+
+/// Atomic English sentences and the formulas they stand for
+/// Keys are normalized: lowercase words separated by single spaces, no commas.
+pub struct Dictionary {
+    sentences: HashMap<String, Expr>,
+}
+
+/// Lowercase words, with each comma as a separate token
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace(',', " , ")
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != ',' && c != '\'').to_string())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn key(words: &[&str]) -> String {
+    let w: Vec<&str> = words.iter().copied().filter(|w| *w != ",").collect();
+    w.join(" ")
+}
+
+impl Dictionary {
+    fn lookup(&self, words: &[&str]) -> Option<&Expr> {
+        self.sentences.get(&key(words))
+    }
+}
+
+/// Read a dictionary file: one `formula = sentence | sentence ...` per line,
+/// '#' starts a comment line
+/// The formula is usually a variable (`r = it rains | it is raining`), but may be
+/// any expression, e.g. `~r = it does not rain` for a negative sentence.
+pub fn load(path: &str) -> Result<Dictionary, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let mut sentences = HashMap::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let at = |msg: String| format!("{}:{}: {}", path, n + 1, msg);
+        let (formula, english) = line.split_once('=').ok_or_else(|| at("expected 'formula = sentence'".to_string()))?;
+        let formula = Parser::new(formula.trim()).parse_all().map_err(at)?;
+        for sentence in english.split('|') {
+            let words = tokenize(sentence);
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            if words.iter().all(|w| *w == ",") { return Err(at("empty sentence".to_string())); }
+            if let Some(old) = sentences.insert(key(&words), formula.clone())
+                && old != formula {
+                return Err(at(format!("\"{}\" already stands for {}", key(&words), pretty(&old, Style::Ascii))));
+            }
+        }
+    }
+    Ok(Dictionary { sentences })
+}
+
+// ============================================================================
+// SENTENCE PATTERNS
+// ============================================================================
+
+/// Words that only occur as connectives; a phrase without them should be atomic
+const CONNECTIVES: [&str; 9] = ["if", "then", "and", "or", "unless", "either", "neither", "nor", "both"];
+
+const NEGATIONS: [&[&str]; 4] = [
+    &["it", "is", "not", "the", "case", "that"],
+    &["it", "is", "false", "that"],
+    &["it", "is", "not", "true", "that"],
+    &["not"],
+];
+
+/// Openings that mark the conclusion of an argument
+const CONCLUSIONS: [&[&str]; 7] = [
+    &["therefore"], &["so"], &["hence"], &["thus"], &["consequently"],
+    &["it", "follows", "that"], &["we", "conclude", "that"],
+];
+
+fn not(e: Expr) -> Expr { Expr::Not(Box::new(e)) }
+fn and(a: Expr, b: Expr) -> Expr { Expr::And(Box::new(a), Box::new(b)) }
+fn or(a: Expr, b: Expr) -> Expr { Expr::Or(Box::new(a), Box::new(b)) }
+fn implies(a: Expr, b: Expr) -> Expr { Expr::Implies(Box::new(a), Box::new(b)) }
+
+/// An infix connective and how it combines the phrases on either side
+type Infix = (&'static [&'static str], fn(Expr, Expr) -> Expr);
+
+/// Infix connectives, loosest first
+const INFIX: [Infix; 5] = [
+    (&["only", "if"], implies),
+    (&["if"], |a, b| implies(b, a)),
+    (&["unless"], |a, b| implies(not(b), a)),
+    (&["or"], or),
+    (&["and"], and),
+];
+
+/// Translator state: the sentence, the phrases translated so far and the
+/// atomic-looking phrases the dictionary lacked
+/// Every phrase is a word range of the sentence, so each range is translated once
+/// however many splits reach it.
+struct Translator<'a> {
+    dict: &'a Dictionary,
+    words: &'a [&'a str],
+    memo: HashMap<(usize, usize), Option<Expr>>,
+    unknown: Vec<String>,
+}
+
+impl Translator<'_> {
+    /// Split words `start..end` at the first occurrence of `sep` where both sides translate
+    fn split(&mut self, start: usize, end: usize, sep: &[&str]) -> Option<(Expr, Expr)> {
+        for i in start + 1..end {
+            if !self.words[i..end].starts_with(sep) { continue; }
+            let Some(a) = self.phrase(start, i) else { continue };
+            if let Some(b) = self.phrase(i + sep.len(), end) { return Some((a, b)); }
+        }
+        None
+    }
+
+    /// Translate words `start..end`, remembering the result for that range
+    fn phrase(&mut self, start: usize, end: usize) -> Option<Expr> {
+        if let Some(e) = self.memo.get(&(start, end)) { return e.clone(); }
+        let e = self.translate(start, end);
+        self.memo.insert((start, end), e.clone());
+        e
+    }
+
+    /// Translate a phrase, trying patterns from the loosest connective inward:
+    ///   - if A then B / if A, B          => A > B
+    ///   - unless A, B                    => ~A > B
+    ///   - either A or B                  => A + B
+    ///   - neither A nor B                => ~A * ~B
+    ///   - both A and B                   => A * B
+    ///   - A only if B                    => A > B
+    ///   - A if B                         => B > A
+    ///   - A unless B                     => ~B > A
+    ///   - A or B                         => A + B
+    ///   - A and B                        => A * B
+    ///   - not A / it is not the case that A / it is false that A  => ~A
+    ///   - an atomic sentence from the dictionary
+    fn translate(&mut self, mut start: usize, mut end: usize) -> Option<Expr> {
+        let words = self.words;
+        while start < end && words[start] == "," { start += 1; }
+        while start < end && words[end - 1] == "," { end -= 1; }
+        let w = &words[start..end];
+        if w.is_empty() { return None; }
+        if let Some(e) = self.dict.lookup(w) { return Some(e.clone()); }
+
+        let leading = match w[0] {
+            "if" => self.split(start + 1, end, &["then"]).or_else(|| self.split(start + 1, end, &[","])).map(|(a, b)| implies(a, b)),
+            "unless" => self.split(start + 1, end, &[","]).map(|(a, b)| implies(not(a), b)),
+            "either" => self.split(start + 1, end, &["or"]).map(|(a, b)| or(a, b)),
+            "neither" => self.split(start + 1, end, &["nor"]).map(|(a, b)| and(not(a), not(b))),
+            "both" => self.split(start + 1, end, &["and"]).map(|(a, b)| and(a, b)),
+            _ => None,
+        };
+        if leading.is_some() { return leading; }
+
+        for (sep, make) in INFIX {
+            if let Some((a, b)) = self.split(start, end, sep) { return Some(make(a, b)); }
+        }
+
+        for neg in NEGATIONS {
+            if w.starts_with(neg) && w.len() > neg.len() {
+                return self.phrase(start + neg.len(), end).map(not);
+            }
+        }
+
+        if !w.iter().any(|x| CONNECTIVES.contains(x)) { self.unknown.push(key(w)); }
+        None
+    }
+}
+
+// ============================================================================
+// ARGUMENTS
+// ============================================================================
+
+/// An argument read from English, keeping each sentence next to its formula
+pub struct Argument {
+    pub premises: Vec<(String, Expr)>,
+    pub conclusion: (String, Expr),
+}
+
+/// Translate one sentence, explaining a failure by the phrases the dictionary lacks
+fn sentence(text: &str, dict: &Dictionary) -> Result<Expr, String> {
+    let tokens = tokenize(text);
+    let words: Vec<&str> = tokens.iter().map(String::as_str).collect();
+    let mut t = Translator { dict, words: &words, memo: HashMap::new(), unknown: Vec::new() };
+    if let Some(e) = t.phrase(0, words.len()) { return Ok(e); }
+
+    // Wrong splits leave fragments such as "it"; report only the longest phrases
+    let mut unknown = t.unknown.clone();
+    unknown.sort();
+    unknown.dedup();
+    let reported: Vec<String> = unknown.iter()
+        .filter(|u| !unknown.iter().any(|v| v != *u && format!(" {} ", v).contains(&format!(" {} ", u))))
+        .map(|u| format!("\"{}\"", u))
+        .collect();
+    if reported.is_empty() {
+        Err(format!("no sentence pattern matches \"{}\"", text))
+    } else {
+        Err(format!("no sentence pattern matches \"{}\"; not in the dictionary: {}", text, reported.join(", ")))
+    }
+}
+
+/// Split a text into sentences and translate them; exactly one sentence must
+/// start with a conclusion marker such as "Therefore" or "So"
+pub fn translate(text: &str, dict: &Dictionary) -> Result<Argument, String> {
+    let mut premises = Vec::new();
+    let mut conclusion = None;
+    for (n, raw) in text.split(['.', '!', ';']).map(str::trim).filter(|s| !s.is_empty()).enumerate() {
+        let at = |msg: String| format!("Sentence {}: {}", n + 1, msg);
+        let tokens = tokenize(raw);
+        let marker = CONCLUSIONS.iter().find(|m| tokens.iter().map(String::as_str).take(m.len()).eq(m.iter().copied()));
+        match marker {
+            Some(m) => {
+                if conclusion.is_some() { return Err(at("the argument already has a conclusion".to_string())); }
+                // Drop the marker words (and a comma after them) from the original text
+                let rest = raw.split_whitespace().skip(m.len()).collect::<Vec<_>>().join(" ");
+                let rest = rest.trim_start_matches(',').trim().to_string();
+                if rest.is_empty() { return Err(at(format!("\"{}\" has no conclusion after it", raw))); }
+                let e = sentence(&rest, dict).map_err(at)?;
+                conclusion = Some((rest, e));
+            }
+            None => premises.push((raw.to_string(), sentence(raw, dict).map_err(at)?)),
+        }
+    }
+    let conclusion = conclusion.ok_or("No conclusion found: start it with \"Therefore\", \"So\" or \"Hence\"")?;
+    Ok(Argument { premises, conclusion })
+}
+
+/// Show each sentence with the formula it became
+pub fn print_translation(arg: &Argument) {
+    println!("Translation:");
+    for (n, (text, e)) in arg.premises.iter().enumerate() {
+        println!("  Premise {}: {}  =>  {}", n + 1, text, pretty(e, Style::Ascii));
+    }
+    println!("  Conclusion: {}  =>  {}", arg.conclusion.0, pretty(&arg.conclusion.1, Style::Ascii));
+    println!();
+}
@@ -0,0 +1,144 @@
+use std::fmt::Write;
+
+use crate::pretty::{pretty, Style};
+use crate::{collect_deps, Expr, Fact, Source};
+
+// ============================================================================
+// PROOF EXPORT
+// ============================================================================
+
+/// Output format for an exported proof
+#[derive(Clone, Copy)]
+pub enum Format {
+    Latex,     // Fitch-style derivation for the `fitch` LaTeX package
+    Markdown,  // Table with step, formula and justification columns
+    Json,      // Steps with rule names and parent step references
+}
+
+/// One line of a linear proof, with parents mapped to 1-based step numbers
+struct Line<'a> {
+    expr: &'a Expr,
+    rule: Option<&'a str>,  // None for premises
+    parents: Vec<usize>,
+}
+
+/// Lay the proof of `target` out in dependency order like `print_proof`, but with
+/// every premise first so they can all sit above the Fitch bar
+fn lines(facts: &[Fact], target: usize) -> Vec<Line<'_>> {
+    let mut steps = Vec::new();
+    collect_deps(facts, target, &mut steps);
+    let (mut steps, derived): (Vec<usize>, Vec<usize>) =
+        steps.into_iter().partition(|&i| matches!(facts[i].source, Source::Premise));
+    steps.extend(derived);
+    steps.iter()
+        .map(|&idx| match &facts[idx].source {
+            Source::Premise => Line { expr: &facts[idx].expr, rule: None, parents: Vec::new() },
+            Source::Derived { rule, parents } => Line {
+                expr: &facts[idx].expr,
+                rule: Some(rule),
+                parents: parents.iter().map(|&p| steps.iter().position(|&x| x == p).unwrap() + 1).collect(),
+            },
+        })
+        .collect()
+}
+
+fn numbers(ns: &[usize]) -> String {
+    let s: Vec<String> = ns.iter().map(|n| n.to_string()).collect();
+    s.join(", ")
+}
+
+/// Render the proof of `target` in the given format
+pub fn render(facts: &[Fact], target: usize, format: Format) -> String {
+    let lines = lines(facts, target);
+    match format {
+        Format::Latex => latex_proof(&lines),
+        Format::Markdown => markdown_proof(&lines),
+        Format::Json => json_proof(&lines),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// LATEX
+// ----------------------------------------------------------------------------
+
+/// Premises become hypotheses above the Fitch bar, derived steps follow with `\by`
+fn latex_proof(lines: &[Line]) -> String {
+    let mut out = String::from("% \\usepackage{fitch}\n$$\n\\begin{nd}\n");
+    for (n, l) in lines.iter().enumerate() {
+        match l.rule {
+            None => { let _ = writeln!(out, "  \\hypo{{{}}}{{{}}}", n + 1, pretty(l.expr, Style::Latex)); }
+            Some(rule) => {
+                let refs: Vec<String> = l.parents.iter().map(|p| p.to_string()).collect();
+                let _ = writeln!(out, "  \\have{{{}}}{{{}}} \\by{{{}}}{{{}}}", n + 1, pretty(l.expr, Style::Latex), rule, refs.join(","));
+            }
+        }
+    }
+    out.push_str("\\end{nd}\n$$\n");
+    out
+}
+
+// ----------------------------------------------------------------------------
+// MARKDOWN
+// ----------------------------------------------------------------------------
+
+fn markdown_proof(lines: &[Line]) -> String {
+    let mut out = String::from("| Step | Formula | Justification |\n|---:|---|---|\n");
+    for (n, l) in lines.iter().enumerate() {
+        let why = match l.rule {
+            None => "Premise".to_string(),
+            Some(rule) => format!("{} ({})", rule, numbers(&l.parents)),
+        };
+        // Code spans keep *, + and ~ from being read as Markdown emphasis
+        let _ = writeln!(out, "| {} | `{}` | {} |", n + 1, pretty(l.expr, Style::Ascii), why);
+    }
+    out
+}
+
+// ----------------------------------------------------------------------------
+// JSON
+// ----------------------------------------------------------------------------
+
+/// Quote a string as a JSON string literal
+pub fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// JSON keeps the fully parenthesized Display form, so that consumers see a
+// stable format that parses back to the same formula
+fn json_proof(lines: &[Line]) -> String {
+    let mut out = String::from("{\n  \"steps\": [\n");
+    for (n, l) in lines.iter().enumerate() {
+        let rule = l.rule.map_or("Premise".to_string(), |r| r.to_string());
+        let _ = write!(out, "    {{\"step\": {}, \"formula\": {}, \"rule\": {}, \"parents\": [{}]}}",
+            n + 1, json_string(&l.expr.to_string()), json_string(&rule), numbers(&l.parents));
+        out.push_str(if n + 1 < lines.len() { ",\n" } else { "\n" });
+    }
+    let _ = writeln!(out, "  ],\n  \"conclusion\": {}", lines.len());
+    out.push_str("}\n");
+    out
+}
+
+/// The same object on a single line, for the JSON-lines protocol
+pub fn json_compact(facts: &[Fact], target: usize) -> String {
+    let lines = lines(facts, target);
+    let steps: Vec<String> = lines.iter().enumerate()
+        .map(|(n, l)| {
+            let rule = l.rule.map_or("Premise".to_string(), |r| r.to_string());
+            format!("{{\"step\": {}, \"formula\": {}, \"rule\": {}, \"parents\": [{}]}}",
+                n + 1, json_string(&l.expr.to_string()), json_string(&rule), numbers(&l.parents))
+        })
+        .collect();
+    format!("{{\"steps\": [{}], \"conclusion\": {}}}", steps.join(", "), lines.len())
+}
@@ -0,0 +1,264 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::kb::{Connective, FactBase};
+use crate::{negate, Expr, Fact, Source};
+
+// ============================================================================
+// TERMS AND SUBSTITUTION
+// ============================================================================
+
+/// Replace free occurrences of the variable `var` with the name `term`
+pub fn subst(e: &Expr, var: &str, term: &str) -> Expr {
+    match e {
+        Expr::Var(_) => e.clone(),
+        Expr::Pred(p, args) => Expr::Pred(
+            p.clone(),
+            args.iter().map(|a| if a == var { term.to_string() } else { a.clone() }).collect(),
+        ),
+        Expr::Not(a) => Expr::Not(Box::new(subst(a, var, term))),
+        Expr::And(a, b) => Expr::And(Box::new(subst(a, var, term)), Box::new(subst(b, var, term))),
+        Expr::Or(a, b) => Expr::Or(Box::new(subst(a, var, term)), Box::new(subst(b, var, term))),
+        Expr::Implies(a, b) => Expr::Implies(Box::new(subst(a, var, term)), Box::new(subst(b, var, term))),
+        // An inner quantifier over the same variable shadows it
+        Expr::Forall(v, _) | Expr::Exists(v, _) if v == var => e.clone(),
+        Expr::Forall(v, a) => Expr::Forall(v.clone(), Box::new(subst(a, var, term))),
+        Expr::Exists(v, a) => Expr::Exists(v.clone(), Box::new(subst(a, var, term))),
+        Expr::Necessarily(a) => Expr::Necessarily(Box::new(subst(a, var, term))),
+        Expr::Possibly(a) => Expr::Possibly(Box::new(subst(a, var, term))),
+    }
+}
+
+/// Collect the names occurring free in predicate arguments (the constants of `e`)
+pub fn free_names(e: &Expr, out: &mut BTreeSet<String>) {
+    fn walk(e: &Expr, bound: &mut Vec<String>, out: &mut BTreeSet<String>) {
+        match e {
+            Expr::Var(_) => {}
+            Expr::Pred(_, args) => {
+                for a in args {
+                    if !bound.contains(a) { out.insert(a.clone()); }
+                }
+            }
+            Expr::Not(a) | Expr::Necessarily(a) | Expr::Possibly(a) => walk(a, bound, out),
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => { walk(a, bound, out); walk(b, bound, out); }
+            Expr::Forall(v, a) | Expr::Exists(v, a) => {
+                bound.push(v.clone());
+                walk(a, bound, out);
+                bound.pop();
+            }
+        }
+    }
+    walk(e, &mut Vec::new(), out);
+}
+
+/// Collect every name used anywhere in `e`: atoms, predicate arguments and bound variables
+fn all_names(e: &Expr, out: &mut HashSet<String>) {
+    match e {
+        Expr::Var(v) => { out.insert(v.clone()); }
+        Expr::Pred(_, args) => out.extend(args.iter().cloned()),
+        Expr::Not(a) | Expr::Necessarily(a) | Expr::Possibly(a) => all_names(a, out),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => { all_names(a, out); all_names(b, out); }
+        Expr::Forall(v, a) | Expr::Exists(v, a) => { out.insert(v.clone()); all_names(a, out); }
+    }
+}
+
+/// True if a quantifier inside `e` binds the name `v` (substituting `v` would be captured)
+pub(crate) fn binds(e: &Expr, v: &str) -> bool {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) => false,
+        Expr::Not(a) | Expr::Necessarily(a) | Expr::Possibly(a) => binds(a, v),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => binds(a, v) || binds(b, v),
+        Expr::Forall(x, a) | Expr::Exists(x, a) => x == v || binds(a, v),
+    }
+}
+
+/// Depth of nested universal quantifiers
+fn forall_depth(e: &Expr) -> usize {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) => 0,
+        Expr::Not(a) | Expr::Exists(_, a) | Expr::Necessarily(a) | Expr::Possibly(a) => forall_depth(a),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => forall_depth(a).max(forall_depth(b)),
+        Expr::Forall(_, a) => 1 + forall_depth(a),
+    }
+}
+
+/// Collect quantified subformulas (and negated ones) that generalization may aim for
+fn quantified_subformulas(e: &Expr, out: &mut Vec<Expr>) {
+    let mut push = |x: Expr| if !out.contains(&x) { out.push(x); };
+    match e {
+        Expr::Not(inner) => match inner.as_ref() {
+            // ~forall x. A is reached from exists x. ~A, and ~exists x. A from forall x. ~A
+            Expr::Forall(v, a) => { push(e.clone()); push(Expr::Exists(v.clone(), Box::new(negate(a)))); }
+            Expr::Exists(v, a) => { push(e.clone()); push(Expr::Forall(v.clone(), Box::new(negate(a)))); }
+            _ => {}
+        },
+        Expr::Forall(..) | Expr::Exists(..) => push(e.clone()),
+        _ => {}
+    }
+    match e {
+        Expr::Var(_) | Expr::Pred(..) => {}
+        Expr::Not(a) | Expr::Forall(_, a) | Expr::Exists(_, a) | Expr::Necessarily(a) | Expr::Possibly(a) => {
+            quantified_subformulas(a, out)
+        }
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => {
+            quantified_subformulas(a, out);
+            quantified_subformulas(b, out);
+        }
+    }
+}
+
+// ============================================================================
+// QUANTIFIER RULES
+// ============================================================================
+
+/// Cap on constants introduced by Existential Instantiation; formulas such as
+/// forall y. exists x. R(x, y) would otherwise keep producing new witnesses
+const MAX_WITNESSES: usize = 16;
+
+/// State for the quantifier rules of forward chaining
+/// Rules implemented:
+///   - Universal Instantiation: forall x. A => A[x:=t] for every known name t
+///   - Existential Instantiation: exists x. A => A[x:=c] for a brand-new constant c
+///   - Universal Generalization: A[x:=u] => forall x. A when u is an arbitrary name
+///   - Existential Generalization: A[x:=t] => exists x. A
+///   - Quantifier Negation: ~forall x. A <=> exists x. ~A, ~exists x. A <=> forall x. ~A
+///
+/// Generalization only fires towards quantified formulas that occur in the premises
+/// or the deduction, the same way Addition and Conjunction are restricted.
+pub struct Quantifiers {
+    constants: Vec<String>,  // Names of individuals (from the input or introduced by EI)
+    arbitrary: Vec<String>,  // Reserved names standing for an arbitrary individual
+    used: HashSet<String>,   // Every name in use, for freshness checks
+    wanted: Vec<Expr>,       // Quantified formulas worth generalizing to
+    witnesses: usize,        // Constants introduced by Existential Instantiation so far
+}
+
+impl Quantifiers {
+    /// Set up the name pools from the premises and the deduction
+    pub fn new(premises: &[Fact], target: &Expr) -> Self {
+        let mut constants = BTreeSet::new();
+        let mut used = HashSet::new();
+        let mut wanted = Vec::new();
+        let exprs = premises.iter().map(|f| &f.expr).chain(std::iter::once(target));
+        for e in exprs {
+            free_names(e, &mut constants);
+            all_names(e, &mut used);
+            quantified_subformulas(e, &mut wanted);
+        }
+
+        // One arbitrary name per level of universal nesting we might generalize over;
+        // at least one whenever quantifiers appear, since the domain is never empty
+        let depth = wanted.iter().map(forall_depth).max().unwrap_or(0);
+        let count = if wanted.is_empty() { 0 } else { depth.max(1) };
+        let mut q = Quantifiers { constants: constants.into_iter().collect(), arbitrary: Vec::new(), used, wanted, witnesses: 0 };
+        for _ in 0..count {
+            let u = q.fresh("u");
+            q.arbitrary.push(u);
+        }
+
+        // Reaching forall y. B by generalization needs B[y:=u] first, so the
+        // quantified parts of each such instance are wanted as well
+        let mut i = 0;
+        while i < q.wanted.len() {
+            if let Expr::Forall(y, body) = q.wanted[i].clone() {
+                for u in &q.arbitrary {
+                    quantified_subformulas(&subst(&body, &y, u), &mut q.wanted);
+                }
+            }
+            i += 1;
+        }
+        q
+    }
+
+    /// Produce a name not used anywhere so far
+    fn fresh(&mut self, prefix: &str) -> String {
+        let mut n = 1;
+        while self.used.contains(&format!("{}{}", prefix, n)) { n += 1; }
+        let name = format!("{}{}", prefix, n);
+        self.used.insert(name.clone());
+        name
+    }
+
+    /// Universal Generalization side conditions: `u` must be an arbitrary name, it must
+    /// not remain free in the generalized formula `w`, and no Existential Instantiation
+    /// in the fact's derivation may depend on `u` (otherwise the witness chosen there
+    /// could depend on the individual being generalized)
+    fn may_generalize(&self, facts: &[Fact], id: usize, u: &str, w: &Expr) -> bool {
+        if !self.arbitrary.iter().any(|a| a == u) { return false; }
+        let mut remaining = BTreeSet::new();
+        free_names(w, &mut remaining);
+        if remaining.contains(u) { return false; }
+        let mut stack = vec![id];
+        let mut visited = HashSet::new();
+        while let Some(i) = stack.pop() {
+            if !visited.insert(i) { continue; }
+            if let Source::Derived { rule, parents } = &facts[i].source {
+                if rule == "Existential Instantiation" {
+                    let mut names = BTreeSet::new();
+                    free_names(&facts[parents[0]].expr, &mut names);
+                    if names.contains(u) { return false; }
+                }
+                stack.extend(parents);
+            }
+        }
+        true
+    }
+
+    /// Apply every quantifier rule to `cur`
+    pub fn apply(&mut self, cur: &Fact, base: &FactBase, out: &mut Vec<(Expr, &'static str, Vec<usize>)>) {
+        let facts = &base.facts;
+        match &cur.expr {
+            Expr::Forall(x, a) => {
+                for t in self.constants.iter().chain(&self.arbitrary) {
+                    if binds(a, t) { continue; }
+                    out.push((subst(a, x, t), "Universal Instantiation", vec![cur.id]));
+                }
+            }
+            Expr::Exists(x, a) if self.witnesses < MAX_WITNESSES => {
+                self.witnesses += 1;
+                let c = self.fresh("c");
+                out.push((subst(a, x, &c), "Existential Instantiation", vec![cur.id]));
+                // Universals processed earlier have not seen the new constant yet
+                for &i in base.with_connective(Connective::Forall) {
+                    if let Expr::Forall(y, b) = &facts[i].expr
+                        && !binds(b, &c) {
+                        out.push((subst(b, y, &c), "Universal Instantiation", vec![i]));
+                    }
+                }
+                self.constants.push(c);
+            }
+            Expr::Not(inner) => match inner.as_ref() {
+                Expr::Forall(x, a) => {
+                    out.push((Expr::Exists(x.clone(), Box::new(negate(a))), "Quantifier Negation", vec![cur.id]));
+                }
+                Expr::Exists(x, a) => {
+                    out.push((Expr::Forall(x.clone(), Box::new(negate(a))), "Quantifier Negation", vec![cur.id]));
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        let mut names = BTreeSet::new();
+        free_names(&cur.expr, &mut names);
+
+        for w in &self.wanted {
+            match w {
+                Expr::Not(inner) => match (inner.as_ref(), &cur.expr) {
+                    (Expr::Exists(x, a), Expr::Forall(y, b)) | (Expr::Forall(x, a), Expr::Exists(y, b))
+                        if x == y && **b == negate(a) => {
+                        out.push((w.clone(), "Quantifier Negation", vec![cur.id]));
+                    }
+                    _ => {}
+                },
+                Expr::Exists(x, b) if names.iter().any(|t| subst(b, x, t) == cur.expr) => {
+                    out.push((w.clone(), "Existential Generalization", vec![cur.id]));
+                }
+                Expr::Forall(x, b)
+                    if names.iter().any(|u| subst(b, x, u) == cur.expr && self.may_generalize(facts, cur.id, u, w)) => {
+                    out.push((w.clone(), "Universal Generalization", vec![cur.id]));
+                }
+                _ => {}
+            }
+        }
+    }
+}
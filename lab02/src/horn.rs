@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Instant;
+
+use crate::{kb, Expr, Fact, Limits, Outcome, Source, Stats};
+
+// ============================================================================
+// HORN CLAUSES
+// ============================================================================
+
+fn is_atom(e: &Expr) -> bool {
+    matches!(e, Expr::Var(_) | Expr::Pred(..))
+}
+
+/// True for an atom or a conjunction of atoms (any nesting)
+fn is_conjunction_of_atoms(e: &Expr) -> bool {
+    match e {
+        Expr::And(a, b) => is_conjunction_of_atoms(a) && is_conjunction_of_atoms(b),
+        _ => is_atom(e),
+    }
+}
+
+/// A premise is a definite Horn clause when it is an atom (a fact) or
+/// `a1 * ... * an > b` with atoms ai and b (a rule)
+fn is_horn(e: &Expr) -> bool {
+    match e {
+        Expr::Implies(body, head) => is_conjunction_of_atoms(body) && is_atom(head),
+        _ => is_atom(e),
+    }
+}
+
+/// True when the fast path decides the argument: every premise is Horn and the
+/// goal is an atom or a conjunction of atoms
+pub fn applies(premises: &[Expr], goal: &Expr) -> bool {
+    !premises.is_empty() && premises.iter().all(is_horn) && is_conjunction_of_atoms(goal)
+}
+
+fn atoms_of(e: &Expr, out: &mut Vec<Expr>) {
+    match e {
+        Expr::And(a, b) => { atoms_of(a, out); atoms_of(b, out); }
+        _ => out.push(e.clone()),
+    }
+}
+
+// ============================================================================
+// COUNTER-BASED PROPAGATION
+// ============================================================================
+
+/// A rule waiting for its body atoms
+struct Rule {
+    premise: usize,    // Fact holding the rule
+    body: Expr,        // Antecedent, rebuilt as a conjunction in the trace
+    head: usize,       // Atom index of the consequent
+    missing: usize,    // Body atoms not yet known to be true
+}
+
+/// Atoms numbered in order of appearance, with the rules watching each one
+#[derive(Default)]
+struct Atoms {
+    ids: HashMap<Expr, usize>,
+    exprs: Vec<Expr>,
+    watchers: Vec<Vec<usize>>,   // Atom -> rules with it in the body
+    known: Vec<Option<usize>>,   // Atom -> fact proving it
+}
+
+impl Atoms {
+    fn id(&mut self, e: &Expr) -> usize {
+        if let Some(&i) = self.ids.get(e) { return i; }
+        let i = self.exprs.len();
+        self.ids.insert(e.clone(), i);
+        self.exprs.push(e.clone());
+        self.watchers.push(Vec::new());
+        self.known.push(None);
+        i
+    }
+}
+
+/// Proof trace under construction: facts in print_proof form without duplicates
+struct Trace {
+    interner: kb::Interner,
+    facts: Vec<Fact>,
+    index: HashMap<Expr, usize>,
+    rules: BTreeMap<String, usize>,
+}
+
+impl Trace {
+    fn add(&mut self, expr: Expr, source: Source) -> usize {
+        if let Some(&i) = self.index.get(&expr) { return i; }
+        if let Source::Derived { rule, .. } = &source {
+            *self.rules.entry(rule.clone()).or_default() += 1;
+        }
+        let id = self.facts.len();
+        let key = self.interner.intern(&expr);
+        self.index.insert(expr.clone(), id);
+        self.facts.push(Fact { expr, source, id, key });
+        id
+    }
+
+    /// Fact for a conjunction of atoms that are all known, adding Conjunction steps as needed
+    fn conjunction(&mut self, e: &Expr, atoms: &Atoms) -> usize {
+        if let Some(&i) = self.index.get(e) { return i; }
+        match e {
+            Expr::And(a, b) => {
+                let l = self.conjunction(a, atoms);
+                let r = self.conjunction(b, atoms);
+                self.add(e.clone(), Source::Derived { rule: "Conjunction".to_string(), parents: vec![l, r] })
+            }
+            _ => atoms.known[atoms.ids[e]].expect("body atom is known"),
+        }
+    }
+}
+
+/// Linear-time forward chaining for definite Horn clauses (Dowling–Gallier):
+/// every rule keeps a count of body atoms not yet true and fires when it reaches
+/// zero, so each rule and each atom is visited a constant number of times.
+/// The proof is recorded with Conjunction and Modus Ponens steps as usual, and
+/// the fact, step and time limits stop the search as in the general engine.
+pub fn forward_chain(premises: &[Expr], goal: &Expr, limits: &Limits) -> Outcome {
+    let start = Instant::now();
+    let mut trace = Trace { interner: kb::Interner::default(), facts: Vec::new(), index: HashMap::new(), rules: Default::default() };
+    let mut atoms = Atoms::default();
+    let mut rules: Vec<Rule> = Vec::new();
+    let mut agenda = VecDeque::new();
+
+    let mut premise_count = 0;
+    for p in premises {
+        if trace.index.contains_key(p) { continue; }
+        let fact = trace.add(p.clone(), Source::Premise);
+        premise_count += 1;
+        match p {
+            Expr::Implies(body, head) => {
+                let mut body_atoms = Vec::new();
+                atoms_of(body, &mut body_atoms);
+                let mut ids: Vec<usize> = body_atoms.iter().map(|a| atoms.id(a)).collect();
+                ids.sort_unstable();
+                ids.dedup();
+                let r = rules.len();
+                for &a in &ids { atoms.watchers[a].push(r); }
+                let head = atoms.id(head);
+                rules.push(Rule { premise: fact, body: *body.clone(), head, missing: ids.len() });
+            }
+            _ => {
+                let a = atoms.id(p);
+                if atoms.known[a].is_none() { atoms.known[a] = Some(fact); agenda.push_back(a); }
+            }
+        }
+    }
+    let mut goal_atoms = Vec::new();
+    atoms_of(goal, &mut goal_atoms);
+    let goal_ids: Vec<usize> = goal_atoms.iter().map(|a| atoms.id(a)).collect();
+
+    let mut steps = 0;
+    let mut limit = None;
+    while !goal_ids.iter().all(|&g| atoms.known[g].is_some()) {
+        if trace.facts.len() >= limits.max_facts { limit = Some("fact"); break; }
+        if steps >= limits.max_steps { limit = Some("step"); break; }
+        if limits.timeout.is_some_and(|t| start.elapsed() >= t) { limit = Some("time"); break; }
+        let Some(a) = agenda.pop_front() else { break };
+        steps += 1;
+        for i in 0..atoms.watchers[a].len() {
+            let rule = &mut rules[atoms.watchers[a][i]];
+            rule.missing -= 1;
+            if rule.missing > 0 || atoms.known[rule.head].is_some() { continue; }
+            let body = trace.conjunction(&rule.body, &atoms);
+            let head = atoms.exprs[rule.head].clone();
+            let fact = trace.add(head, Source::Derived { rule: "Modus Ponens".to_string(), parents: vec![rule.premise, body] });
+            atoms.known[rule.head] = Some(fact);
+            agenda.push_back(rule.head);
+        }
+    }
+
+    let proof = goal_ids.iter().all(|&g| atoms.known[g].is_some())
+        .then(|| trace.conjunction(goal, &atoms));
+    let verdict = if proof.is_some() { "Valid" } else { "Insufficient Information" };
+    let stats = Stats {
+        premises: premise_count,
+        steps,
+        rules: trace.rules,
+        interned: trace.interner.node_count(),
+        elapsed: start.elapsed(),
+        horn: true,
+        limit,
+        ..Stats::default()
+    };
+    let alternatives = vec![Vec::new(); trace.facts.len()];
+    Outcome { facts: trace.facts, alternatives, proof, verdict, stats }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Expr, Fact, Source};
+
+// ============================================================================
+// HASH-CONSED EXPRESSIONS
+// ============================================================================
+
+/// Identifier of an interned expression: equal expressions get equal ids
+pub type ExprId = usize;
+
+/// One interned node; children are referenced by id so equality is a single compare
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Node {
+    Var(String),
+    Not(ExprId),
+    And(ExprId, ExprId),
+    Or(ExprId, ExprId),
+    Implies(ExprId, ExprId),
+    Atom(Expr),  // Predicates, quantified and modal formulas, opaque to the propositional rules
+}
+
+/// Table of unique expression nodes
+#[derive(Default)]
+pub struct Interner {
+    nodes: Vec<Node>,
+    ids: HashMap<Node, ExprId>,
+}
+
+impl Interner {
+    /// Return the id of a node, adding it to the table if it is new
+    pub fn node_id(&mut self, node: Node) -> ExprId {
+        if let Some(&id) = self.ids.get(&node) { return id; }
+        let id = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        id
+    }
+
+    /// Intern an expression bottom-up
+    pub fn intern(&mut self, e: &Expr) -> ExprId {
+        let node = match e {
+            Expr::Var(s) => Node::Var(s.clone()),
+            Expr::Not(a) => Node::Not(self.intern(a)),
+            Expr::And(a, b) => Node::And(self.intern(a), self.intern(b)),
+            Expr::Or(a, b) => Node::Or(self.intern(a), self.intern(b)),
+            Expr::Implies(a, b) => Node::Implies(self.intern(a), self.intern(b)),
+            Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+                Node::Atom(e.clone())
+            }
+        };
+        self.node_id(node)
+    }
+
+    pub fn node(&self, id: ExprId) -> &Node {
+        &self.nodes[id]
+    }
+
+    /// Rebuild the expression tree for an id
+    pub fn expr(&self, id: ExprId) -> Expr {
+        match &self.nodes[id] {
+            Node::Var(s) => Expr::Var(s.clone()),
+            Node::Not(a) => Expr::Not(Box::new(self.expr(*a))),
+            Node::And(a, b) => Expr::And(Box::new(self.expr(*a)), Box::new(self.expr(*b))),
+            Node::Or(a, b) => Expr::Or(Box::new(self.expr(*a)), Box::new(self.expr(*b))),
+            Node::Implies(a, b) => Expr::Implies(Box::new(self.expr(*a)), Box::new(self.expr(*b))),
+            Node::Atom(e) => e.clone(),
+        }
+    }
+
+    /// Same as `negate` on expressions: ~~A becomes A
+    pub fn negate(&mut self, id: ExprId) -> ExprId {
+        match self.nodes[id] {
+            Node::Not(inner) => inner,
+            _ => self.node_id(Node::Not(id)),
+        }
+    }
+
+    /// Every id whose negation is `id` (A itself is ~A negated, and ~~A negates to ~A)
+    pub fn negated_by(&mut self, id: ExprId) -> Vec<ExprId> {
+        let n = self.negate(id);
+        let nn = self.node_id(Node::Not(id));
+        if n == nn { vec![n] } else { vec![n, nn] }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+// ============================================================================
+// INDEXED FACT BASE
+// ============================================================================
+
+/// Main connective of a fact, used to find facts of one shape quickly
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Connective {
+    Var,
+    Not,
+    And,
+    Or,
+    Implies,
+    Pred,
+    Forall,
+    Exists,
+    Modal,
+}
+
+/// Known facts plus the indexes the inference rules use to find partners
+#[derive(Default)]
+pub struct FactBase {
+    pub facts: Vec<Fact>,
+    by_key: HashMap<ExprId, usize>,                          // Expression -> fact holding it
+    by_connective: HashMap<Connective, Vec<usize>>,          // Main connective -> facts
+    by_antecedent: HashMap<ExprId, Vec<usize>>,              // A -> implications (A > B)
+    by_consequent: HashMap<ExprId, Vec<usize>>,              // B -> implications (A > B)
+    by_disjunct: HashMap<ExprId, Vec<usize>>,                // A and B -> disjunctions (A + B)
+    or_antecedents: HashMap<ExprId, Vec<ExprId>>,            // A and B -> antecedents (A + B) of implications
+    and_antecedents: HashMap<ExprId, Vec<ExprId>>,           // A and B -> antecedents (A * B) of implications
+    antecedents: HashSet<ExprId>,                            // Antecedents already in the two maps above
+}
+
+fn push_index(map: &mut HashMap<ExprId, Vec<usize>>, key: ExprId, value: usize) {
+    map.entry(key).or_default().push(value);
+}
+
+impl FactBase {
+    /// Fact index holding the given expression, if known
+    pub fn find(&self, key: ExprId) -> Option<usize> {
+        self.by_key.get(&key).copied()
+    }
+
+    pub fn with_connective(&self, c: Connective) -> &[usize] {
+        self.by_connective.get(&c).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn with_antecedent(&self, key: ExprId) -> &[usize] {
+        self.by_antecedent.get(&key).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn with_consequent(&self, key: ExprId) -> &[usize] {
+        self.by_consequent.get(&key).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn with_disjunct(&self, key: ExprId) -> &[usize] {
+        self.by_disjunct.get(&key).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn or_antecedents(&self, key: ExprId) -> &[ExprId] {
+        self.or_antecedents.get(&key).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn and_antecedents(&self, key: ExprId) -> &[ExprId] {
+        self.and_antecedents.get(&key).map_or(&[], |v| v.as_slice())
+    }
+
+    /// Add a fact unless an equal one exists; returns its index when added
+    pub fn add(&mut self, interner: &Interner, key: ExprId, source: Source) -> Option<usize> {
+        if self.by_key.contains_key(&key) { return None; }
+        let idx = self.facts.len();
+        let expr = interner.expr(key);
+        let connective = match &expr {
+            Expr::Var(_) => Connective::Var,
+            Expr::Not(_) => Connective::Not,
+            Expr::And(..) => Connective::And,
+            Expr::Or(..) => Connective::Or,
+            Expr::Implies(..) => Connective::Implies,
+            Expr::Pred(..) => Connective::Pred,
+            Expr::Forall(..) => Connective::Forall,
+            Expr::Exists(..) => Connective::Exists,
+            Expr::Necessarily(_) | Expr::Possibly(_) => Connective::Modal,
+        };
+
+        self.by_key.insert(key, idx);
+        self.by_connective.entry(connective).or_default().push(idx);
+        match *interner.node(key) {
+            Node::Implies(a, b) => {
+                push_index(&mut self.by_antecedent, a, idx);
+                push_index(&mut self.by_consequent, b, idx);
+                if self.antecedents.insert(a) {
+                    match *interner.node(a) {
+                        Node::Or(l, r) => {
+                            push_index(&mut self.or_antecedents, l, a);
+                            if r != l { push_index(&mut self.or_antecedents, r, a); }
+                        }
+                        Node::And(l, r) => {
+                            push_index(&mut self.and_antecedents, l, a);
+                            if r != l { push_index(&mut self.and_antecedents, r, a); }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Node::Or(a, b) => {
+                push_index(&mut self.by_disjunct, a, idx);
+                if b != a { push_index(&mut self.by_disjunct, b, idx); }
+            }
+            _ => {}
+        }
+
+        self.facts.push(Fact { expr, source, id: idx, key });
+        Some(idx)
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::kb::{Connective, FactBase};
+use crate::pretty::{pretty, Style};
+use crate::{analysis, sat, Expr, Parser};
+
+// ============================================================================
+// LEMMA SCHEMAS
+// ============================================================================
+
+/// A proven schema `H1 * ... * Hn > C` used as an extra inference rule
+/// In a schema, a variable whose name starts with an uppercase letter is a
+/// metavariable that matches any expression; other names match only themselves.
+pub struct Lemma {
+    pub name: String,
+    pub rule: String,          // Name cited in proof traces ("Lemma <name>")
+    pub schema: Expr,
+    hypotheses: Vec<Expr>,     // Conjuncts of the antecedent
+    conclusion: Expr,
+}
+
+fn is_meta(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+fn metavariables(e: &Expr, out: &mut Vec<String>) {
+    match e {
+        Expr::Var(v) => if is_meta(v) && !out.contains(v) { out.push(v.clone()); },
+        Expr::Pred(..) => {}
+        Expr::Not(a) | Expr::Forall(_, a) | Expr::Exists(_, a) | Expr::Necessarily(a) | Expr::Possibly(a) => {
+            metavariables(a, out)
+        }
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => { metavariables(a, out); metavariables(b, out); }
+    }
+}
+
+fn conjuncts(e: &Expr, out: &mut Vec<Expr>) {
+    match e {
+        Expr::And(a, b) => { conjuncts(a, out); conjuncts(b, out); }
+        _ => out.push(e.clone()),
+    }
+}
+
+impl Lemma {
+    /// Check a schema can be used as a rule: an implication whose conclusion
+    /// only uses metavariables bound by the hypotheses
+    pub fn new(name: &str, schema: Expr) -> Result<Self, String> {
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("Invalid lemma name: {}", name));
+        }
+        let Expr::Implies(antecedent, conclusion) = &schema else {
+            return Err(format!("Lemma {} must be an implication H > C", name));
+        };
+        let mut hypotheses = Vec::new();
+        conjuncts(antecedent, &mut hypotheses);
+        let (mut bound, mut used) = (Vec::new(), Vec::new());
+        metavariables(antecedent, &mut bound);
+        metavariables(conclusion, &mut used);
+        if let Some(free) = used.iter().find(|v| !bound.contains(v)) {
+            return Err(format!("Lemma {}: metavariable {} appears only in the conclusion", name, free));
+        }
+        Ok(Lemma {
+            name: name.to_string(),
+            rule: format!("Lemma {}", name),
+            hypotheses,
+            conclusion: *conclusion.clone(),
+            schema,
+        })
+    }
+
+    /// Every conclusion the lemma yields with the fact at `idx` matching one of its
+    /// hypotheses and known facts matching the others, with the facts used
+    pub fn instances(&self, base: &FactBase, idx: usize) -> Vec<(Expr, Vec<usize>)> {
+        let mut out = Vec::new();
+        for k in 0..self.hypotheses.len() {
+            let mut bindings = BTreeMap::new();
+            if !unify(&self.hypotheses[k], &base.facts[idx].expr, &mut bindings) { continue; }
+            let mut chosen = vec![None; self.hypotheses.len()];
+            chosen[k] = Some(idx);
+            self.extend(base, &mut chosen, bindings, &mut out);
+        }
+        out
+    }
+
+    /// Match the remaining hypotheses one at a time, backtracking over candidates
+    fn extend(&self, base: &FactBase, chosen: &mut Vec<Option<usize>>, bindings: BTreeMap<String, Expr>,
+              out: &mut Vec<(Expr, Vec<usize>)>) {
+        let Some(h) = chosen.iter().position(Option::is_none) else {
+            let mut parents: Vec<usize> = Vec::new();
+            for &c in chosen.iter().flatten() {
+                if !parents.contains(&c) { parents.push(c); }
+            }
+            out.push((substitute(&self.conclusion, &bindings), parents));
+            return;
+        };
+        let pattern = &self.hypotheses[h];
+        let candidates: Vec<usize> = match connective(pattern) {
+            Some(c) => base.with_connective(c).to_vec(),
+            None => (0..base.facts.len()).collect(),
+        };
+        for i in candidates {
+            let mut b = bindings.clone();
+            if unify(pattern, &base.facts[i].expr, &mut b) {
+                chosen[h] = Some(i);
+                self.extend(base, chosen, b, out);
+                chosen[h] = None;
+            }
+        }
+    }
+}
+
+/// Main connective a fact must have to match `pattern` (None for a metavariable)
+fn connective(pattern: &Expr) -> Option<Connective> {
+    Some(match pattern {
+        Expr::Var(v) if is_meta(v) => return None,
+        Expr::Var(_) => Connective::Var,
+        Expr::Not(_) => Connective::Not,
+        Expr::And(..) => Connective::And,
+        Expr::Or(..) => Connective::Or,
+        Expr::Implies(..) => Connective::Implies,
+        Expr::Pred(..) => Connective::Pred,
+        Expr::Forall(..) => Connective::Forall,
+        Expr::Exists(..) => Connective::Exists,
+        Expr::Necessarily(_) | Expr::Possibly(_) => Connective::Modal,
+    })
+}
+
+// ============================================================================
+// UNIFICATION
+// ============================================================================
+
+/// Extend `bindings` so that `pattern` instantiated by them equals `e`
+/// Facts are ground, so unification reduces to matching the pattern against them;
+/// a metavariable bound earlier must match the same expression again.
+fn unify(pattern: &Expr, e: &Expr, bindings: &mut BTreeMap<String, Expr>) -> bool {
+    match (pattern, e) {
+        (Expr::Var(v), _) if is_meta(v) => match bindings.get(v) {
+            Some(bound) => bound == e,
+            None => { bindings.insert(v.clone(), e.clone()); true }
+        },
+        (Expr::Var(a), Expr::Var(b)) => a == b,
+        (Expr::Pred(p, xs), Expr::Pred(q, ys)) => p == q && xs == ys,
+        (Expr::Not(a), Expr::Not(b))
+        | (Expr::Necessarily(a), Expr::Necessarily(b))
+        | (Expr::Possibly(a), Expr::Possibly(b)) => unify(a, b, bindings),
+        (Expr::And(a1, b1), Expr::And(a2, b2))
+        | (Expr::Or(a1, b1), Expr::Or(a2, b2))
+        | (Expr::Implies(a1, b1), Expr::Implies(a2, b2)) => unify(a1, a2, bindings) && unify(b1, b2, bindings),
+        (Expr::Forall(x, a), Expr::Forall(y, b)) | (Expr::Exists(x, a), Expr::Exists(y, b)) => x == y && unify(a, b, bindings),
+        _ => false,
+    }
+}
+
+/// Replace metavariables by their bindings
+fn substitute(e: &Expr, bindings: &BTreeMap<String, Expr>) -> Expr {
+    match e {
+        Expr::Var(v) => bindings.get(v).cloned().unwrap_or_else(|| e.clone()),
+        Expr::Pred(..) => e.clone(),
+        Expr::Not(a) => Expr::Not(Box::new(substitute(a, bindings))),
+        Expr::And(a, b) => Expr::And(Box::new(substitute(a, bindings)), Box::new(substitute(b, bindings))),
+        Expr::Or(a, b) => Expr::Or(Box::new(substitute(a, bindings)), Box::new(substitute(b, bindings))),
+        Expr::Implies(a, b) => Expr::Implies(Box::new(substitute(a, bindings)), Box::new(substitute(b, bindings))),
+        Expr::Forall(x, a) => Expr::Forall(x.clone(), Box::new(substitute(a, bindings))),
+        Expr::Exists(x, a) => Expr::Exists(x.clone(), Box::new(substitute(a, bindings))),
+        Expr::Necessarily(a) => Expr::Necessarily(Box::new(substitute(a, bindings))),
+        Expr::Possibly(a) => Expr::Possibly(Box::new(substitute(a, bindings))),
+    }
+}
+
+// ============================================================================
+// LEMMA FILES
+// ============================================================================
+
+/// Read a lemma file: one `name: schema` per line, '#' starts a comment line
+/// A schema without quantifiers or modal operators must be a propositional
+/// tautology, or the file is rejected; quantified and modal schemas are loaded
+/// with a warning, since the SAT solver cannot recheck them.
+pub fn load(path: &str) -> Result<Vec<Lemma>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let mut lemmas: Vec<Lemma> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let at = |msg: String| format!("{}:{}: {}", path, n + 1, msg);
+        let (name, schema) = line.split_once(':').ok_or_else(|| at("expected 'name: schema'".to_string()))?;
+        let schema = Parser::new(schema.trim()).parse_all().map_err(at)?;
+        let lemma = Lemma::new(name.trim(), schema).map_err(at)?;
+        if lemmas.iter().any(|l| l.name == lemma.name) {
+            return Err(at(format!("duplicate lemma {}", lemma.name)));
+        }
+        if !sat::entails(&[], &lemma.schema) {
+            if !analysis::has_quantifier(&lemma.schema) {
+                return Err(at(format!("lemma {} is not a tautology: {}", lemma.name, pretty(&lemma.schema, Style::Ascii))));
+            }
+            eprintln!("Warning: lemma {} is first-order or modal and cannot be checked; using it unchecked", lemma.name);
+        }
+        lemmas.push(lemma);
+    }
+    Ok(lemmas)
+}
+
+/// Turn a proven argument into a schema: (P1 * ... * Pn) > C with every
+/// propositional variable replaced by a metavariable A, B, C, ...
+pub fn schema_from(premises: &[Expr], goal: &Expr) -> Option<Expr> {
+    let antecedent = premises.iter().cloned()
+        .reduce(|acc, p| Expr::And(Box::new(acc), Box::new(p)))?;
+    let mut names: BTreeMap<String, Expr> = BTreeMap::new();
+    let mut order = Vec::new();
+    fn vars(e: &Expr, out: &mut Vec<String>) {
+        match e {
+            Expr::Var(v) => if !out.contains(v) { out.push(v.clone()); },
+            Expr::Pred(..) => {}
+            Expr::Not(a) | Expr::Forall(_, a) | Expr::Exists(_, a) | Expr::Necessarily(a) | Expr::Possibly(a) => vars(a, out),
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => { vars(a, out); vars(b, out); }
+        }
+    }
+    vars(&antecedent, &mut order);
+    vars(goal, &mut order);
+    for (i, v) in order.into_iter().enumerate() {
+        let letter = (b'A' + (i % 26) as u8) as char;
+        let meta = if i < 26 { letter.to_string() } else { format!("{}{}", letter, i / 26) };
+        names.insert(v, Expr::Var(meta));
+    }
+    Some(Expr::Implies(Box::new(substitute(&antecedent, &names)), Box::new(substitute(goal, &names))))
+}
+
+/// Append a lemma to the file, refusing to reuse a name
+pub fn save(path: &str, lemma: &Lemma) -> Result<(), String> {
+    let mut text = fs::read_to_string(path).unwrap_or_default();
+    let taken = text.lines()
+        .filter_map(|l| l.split_once(':'))
+        .any(|(n, _)| n.trim() == lemma.name);
+    if taken { return Err(format!("{} already has a lemma named {}", path, lemma.name)); }
+    if !text.is_empty() && !text.ends_with('\n') { text.push('\n'); }
+    text.push_str(&format!("{}: {}\n", lemma.name, pretty(&lemma.schema, Style::Ascii)));
+    fs::write(path, text).map_err(|e| format!("Could not write {}: {}", path, e))
+}
@@ -1,12 +1,39 @@
 use std::fmt;
 use std::io::{self, BufRead};
-use std::collections::HashSet;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::process;
+use std::time::{Duration, Instant};
+
+mod analysis;
+mod batch;
+mod cnf;
+mod english;
+mod export;
+mod fol;
+mod horn;
+mod kb;
+mod lemma;
+mod manyvalued;
+mod modal;
+mod normal;
+mod pretty;
+mod proof;
+mod protocol;
+mod repl;
+mod resolution;
+mod sat;
+mod simplify;
+mod tableau;
 
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
-/// Represents a logical expression in propositional logic
+/// Represents a logical expression in propositional, first-order or modal logic
+/// Terms inside predicates are plain names: a name bound by an enclosing
+/// quantifier is a variable, any other name is a constant.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 enum Expr {
     Var(String),                          // Variable (e.g., "p", "q")
@@ -14,6 +41,11 @@ enum Expr {
     And(Box<Expr>, Box<Expr>),            // Conjunction (A * B)
     Or(Box<Expr>, Box<Expr>),             // Disjunction (A + B)
     Implies(Box<Expr>, Box<Expr>),        // Implication (A > B)
+    Pred(String, Vec<String>),            // Predicate applied to terms (e.g., "M(x)")
+    Forall(String, Box<Expr>),            // Universal quantifier (forall x. A)
+    Exists(String, Box<Expr>),            // Existential quantifier (exists x. A)
+    Necessarily(Box<Expr>),               // Modal box ([]A, □A)
+    Possibly(Box<Expr>),                  // Modal diamond (<>A, ◇A)
 }
 
 /// Display implementation for pretty-printing expressions
@@ -25,6 +57,11 @@ impl fmt::Display for Expr {
             Expr::And(l, r) => write!(f, "({} * {})", l, r),
             Expr::Or(l, r) => write!(f, "({} + {})", l, r),
             Expr::Implies(l, r) => write!(f, "({} > {})", l, r),
+            Expr::Pred(p, args) => write!(f, "{}({})", p, args.join(", ")),
+            Expr::Forall(v, e) => write!(f, "forall {}. {}", v, e),
+            Expr::Exists(v, e) => write!(f, "exists {}. {}", v, e),
+            Expr::Necessarily(e) => write!(f, "[]{}", e),
+            Expr::Possibly(e) => write!(f, "<>{}", e),
         }
     }
 }
@@ -32,9 +69,10 @@ impl fmt::Display for Expr {
 /// Represents a fact in our knowledge base with its derivation history
 #[derive(Clone)]
 struct Fact {
-    expr: Expr,      // The logical expression
-    source: Source,  // How this fact was derived
-    id: usize,       // Unique identifier for dependency tracking
+    expr: Expr,        // The logical expression
+    source: Source,    // How this fact was derived
+    id: usize,         // Unique identifier for dependency tracking
+    key: kb::ExprId,   // Interned id of the expression
 }
 
 /// Tracks how a fact was derived (either a premise or derived from other facts)
@@ -51,30 +89,40 @@ enum Source {
 // PARSER
 // ============================================================================
 
-/// Recursive descent parser for propositional logic expressions
+/// Recursive descent parser for propositional and first-order logic expressions
+/// Operators may also be written in Unicode (¬ ∧ ∨ → □ ◇) or as LaTeX commands
+/// (\lnot \land \lor \to \Box \Diamond \forall \exists), so every `pretty`
+/// style reads back.
 /// Grammar:
 ///   expr    -> implies
 ///   implies -> or ('>' implies)?
 ///   or      -> and ('+' and)*
 ///   and     -> not ('*' not)*
-///   not     -> '~' not | primary
-///   primary -> '(' expr ')' | variable
+///   not     -> '~' not | '[]' not | '<>' not | quant var '.'? not | primary
+///   quant   -> 'forall' | 'exists' | '∀' | '∃'
+///   primary -> '(' expr ')' | name '(' name (',' name)* ')' | variable
 struct Parser {
-    tokens: Vec<char>,  // Input tokenized as characters
+    tokens: Vec<char>,  // Input as characters (whitespace separates names)
     pos: usize,         // Current position in token stream
 }
 
 impl Parser {
-    /// Create a new parser from input string (whitespace is filtered out)
+    /// Create a new parser from input string
     fn new(input: &str) -> Self {
         Parser { 
-            tokens: input.chars().filter(|c| !c.is_whitespace()).collect(), 
+            tokens: input.chars().collect(), 
             pos: 0 
         }
     }
 
+    /// Skip whitespace between tokens
+    fn skip_ws(&mut self) {
+        while self.tokens.get(self.pos).is_some_and(|c| c.is_whitespace()) { self.pos += 1; }
+    }
+
     /// Look at the current token without consuming it
-    fn peek(&self) -> Option<char> {
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
         self.tokens.get(self.pos).copied()
     }
 
@@ -85,16 +133,102 @@ impl Parser {
         c
     }
 
+    /// Read a name made of alphanumeric characters (no whitespace inside)
+    fn name(&mut self) -> Result<String, String> {
+        match self.consume() {
+            Some(c) if c.is_alphanumeric() => {
+                let mut s = c.to_string();
+                while let Some(&nc) = self.tokens.get(self.pos) {
+                    if nc.is_alphanumeric() { self.pos += 1; s.push(nc); }
+                    else { break; }
+                }
+                Ok(s)
+            }
+            Some(c) => Err(format!("Expected a name, found: {}", c)),
+            None => Err("Expected a name, found end of input".to_string()),
+        }
+    }
+
+    /// Length of the LaTeX command `cmd` if the input continues with it
+    fn latex_command(&self, cmd: &str) -> Option<usize> {
+        let len = cmd.chars().count();
+        let matches = self.tokens[self.pos..].iter().take(len).copied().eq(cmd.chars());
+        // A command made of letters must not run on into more letters (\to vs \top)
+        let ends = !cmd.ends_with(char::is_alphabetic)
+            || !self.tokens.get(self.pos + len).is_some_and(|c| c.is_alphabetic());
+        (matches && ends).then_some(len)
+    }
+
+    /// Look at the next operator without consuming it: its ASCII form and length
+    /// (the modal operators are reported as □ and ◇, having no one-character ASCII form)
+    fn peek_op(&mut self) -> Option<(char, usize)> {
+        const LATEX: &[(&str, char)] = &[
+            ("\\lnot", '~'), ("\\neg", '~'), ("\\land", '*'), ("\\wedge", '*'),
+            ("\\lor", '+'), ("\\vee", '+'), ("\\to", '>'), ("\\rightarrow", '>'),
+            ("\\Box", '□'), ("\\Diamond", '◇'),
+        ];
+        match self.peek()? {
+            c @ ('~' | '*' | '+' | '>') => Some((c, 1)),
+            '¬' => Some(('~', 1)),
+            '∧' => Some(('*', 1)),
+            '∨' => Some(('+', 1)),
+            '→' => Some(('>', 1)),
+            c @ ('□' | '◇') => Some((c, 1)),
+            '[' if self.tokens.get(self.pos + 1) == Some(&']') => Some(('□', 2)),
+            '<' if self.tokens.get(self.pos + 1) == Some(&'>') => Some(('◇', 2)),
+            '\\' => LATEX.iter().find_map(|&(cmd, op)| self.latex_command(cmd).map(|len| (op, len))),
+            _ => None,
+        }
+    }
+
+    /// Consume the next operator if it is `op` (in any notation)
+    fn eat_op(&mut self, op: char) -> bool {
+        match self.peek_op() {
+            Some((c, len)) if c == op => { self.pos += len; true }
+            _ => false,
+        }
+    }
+
+    /// Check whether the next name is a quantifier keyword, without consuming it
+    fn peek_quantifier(&mut self) -> Option<(bool, usize)> {
+        match self.peek()? {
+            '∀' => Some((true, 1)),
+            '∃' => Some((false, 1)),
+            '\\' => self.latex_command("\\forall").map(|len| (true, len))
+                .or_else(|| self.latex_command("\\exists").map(|len| (false, len))),
+            _ => {
+                let word: String = self.tokens[self.pos..].iter().take_while(|c| c.is_alphanumeric()).collect();
+                let next = self.tokens.get(self.pos + word.len());
+                // Keywords must be followed by whitespace before the bound variable
+                if !next.is_some_and(|c| c.is_whitespace()) { return None; }
+                match word.as_str() {
+                    "forall" => Some((true, word.len())),
+                    "exists" => Some((false, word.len())),
+                    _ => None,
+                }
+            }
+        }
+    }
+
     /// Entry point: parse a complete expression
     fn parse_expr(&mut self) -> Result<Expr, String> {
         self.parse_implies()
     }
 
+    /// Parse the whole input as one expression; anything left over is an error
+    /// (so that "p q" is rejected rather than read as "p")
+    fn parse_all(&mut self) -> Result<Expr, String> {
+        let e = self.parse_expr()?;
+        match self.peek() {
+            Some(c) => Err(format!("Unexpected character: {}", c)),
+            None => Ok(e),
+        }
+    }
+
     /// Parse implication (lowest precedence, right-associative)
     fn parse_implies(&mut self) -> Result<Expr, String> {
         let lhs = self.parse_or()?;
-        if self.peek() == Some('>') {
-            self.consume();
+        if self.eat_op('>') {
             Ok(Expr::Implies(Box::new(lhs), Box::new(self.parse_implies()?)))
         } else {
             Ok(lhs)
@@ -104,8 +238,7 @@ impl Parser {
     /// Parse disjunction (OR, left-associative)
     fn parse_or(&mut self) -> Result<Expr, String> {
         let mut lhs = self.parse_and()?;
-        while self.peek() == Some('+') {
-            self.consume();
+        while self.eat_op('+') {
             lhs = Expr::Or(Box::new(lhs), Box::new(self.parse_and()?));
         }
         Ok(lhs)
@@ -114,39 +247,57 @@ impl Parser {
     /// Parse conjunction (AND, left-associative)
     fn parse_and(&mut self) -> Result<Expr, String> {
         let mut lhs = self.parse_not()?;
-        while self.peek() == Some('*') {
-            self.consume();
+        while self.eat_op('*') {
             lhs = Expr::And(Box::new(lhs), Box::new(self.parse_not()?));
         }
         Ok(lhs)
     }
 
-    /// Parse negation (prefix operator, right-associative)
+    /// Parse negation, modal operators and quantifiers (prefix operators, right-associative)
+    /// A quantifier binds as tightly as negation: "forall x. M(x) > D(x)" quantifies
+    /// only M(x); write "forall x. (M(x) > D(x))" to quantify the implication.
     fn parse_not(&mut self) -> Result<Expr, String> {
-        if self.peek() == Some('~') {
-            self.consume();
+        if self.eat_op('~') {
             Ok(Expr::Not(Box::new(self.parse_not()?)))
+        } else if self.eat_op('□') {
+            Ok(Expr::Necessarily(Box::new(self.parse_not()?)))
+        } else if self.eat_op('◇') {
+            Ok(Expr::Possibly(Box::new(self.parse_not()?)))
+        } else if let Some((universal, len)) = self.peek_quantifier() {
+            self.pos += len;
+            let var = self.name()?;
+            // The separator is an optional '.' (or a LaTeX thin space \,)
+            if self.peek() == Some('.') { self.consume(); }
+            else if let Some(len) = self.latex_command("\\,") { self.pos += len; }
+            let body = Box::new(self.parse_not()?);
+            Ok(if universal { Expr::Forall(var, body) } else { Expr::Exists(var, body) })
         } else {
             self.parse_primary()
         }
     }
 
-    /// Parse primary expressions: variables or parenthesized expressions
+    /// Parse primary expressions: variables, predicates or parenthesized expressions
     fn parse_primary(&mut self) -> Result<Expr, String> {
-        match self.consume() {
+        match self.peek() {
             Some('(') => {
+                self.consume();
                 let expr = self.parse_expr()?;
                 if self.consume() == Some(')') { Ok(expr) } 
                 else { Err("Expected closing parenthesis".to_string()) }
             }
             Some(c) if c.is_alphanumeric() => {
-                // Read multi-character variable names
-                let mut s = c.to_string();
-                while let Some(nc) = self.peek() {
-                    if nc.is_alphanumeric() { self.consume(); s.push(nc); } 
-                    else { break; }
+                // Read multi-character names; a following '(' makes it a predicate
+                let s = self.name()?;
+                if self.peek() != Some('(') { return Ok(Expr::Var(s)); }
+                self.consume();
+                let mut args = vec![self.name()?];
+                loop {
+                    match self.consume() {
+                        Some(',') => args.push(self.name()?),
+                        Some(')') => return Ok(Expr::Pred(s, args)),
+                        _ => return Err(format!("Expected ',' or ')' in arguments of {}", s)),
+                    }
                 }
-                Ok(Expr::Var(s))
             }
             Some(c) => Err(format!("Unexpected character: {}", c)),
             None => Err("Unexpected end of input".to_string()),
@@ -166,147 +317,386 @@ fn negate(e: &Expr) -> Expr {
     }
 }
 
-/// Main solver: attempts to prove or disprove the deduction from given premises
-/// Uses forward chaining with inference rules to derive new facts until:
+/// Resource limits for forward chaining
+#[derive(Clone, Copy)]
+struct Limits {
+    max_facts: usize,            // Stop once the fact base holds this many facts
+    max_steps: usize,            // Stop after processing this many facts
+    timeout: Option<Duration>,   // Stop after this much wall-clock time
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits { max_facts: 10000, max_steps: usize::MAX, timeout: None }
+    }
+}
+
+/// Counters reported after a forward-chaining run
+#[derive(Default)]
+struct Stats {
+    premises: usize,                     // Distinct premises loaded
+    steps: usize,                        // Facts processed
+    duplicates: usize,                   // Derived facts discarded as already known
+    rules: BTreeMap<String, usize>,      // Facts added per inference rule
+    interned: usize,                     // Distinct expression nodes
+    elapsed: Duration,
+    limit: Option<&'static str>,         // Which limit stopped the search, if any
+    horn: bool,                          // Decided by the linear-time Horn algorithm
+}
+
+impl Stats {
+    fn print(&self, facts: usize) {
+        println!("\nStatistics: {} facts ({} premises, {} derived), {} steps, {} duplicates discarded, {} interned nodes, {:.3} ms",
+            facts, self.premises, facts - self.premises, self.steps, self.duplicates, self.interned,
+            self.elapsed.as_secs_f64() * 1000.0);
+        if !self.rules.is_empty() {
+            let used: Vec<String> = self.rules.iter().map(|(r, n)| format!("{} x{}", r, n)).collect();
+            println!("Rules used: {}", used.join(", "));
+        }
+        if let Some(limit) = self.limit {
+            println!("Search stopped: {} limit reached", limit);
+        }
+    }
+}
+
+/// Result of forward chaining: the fact base, the deciding fact and the verdict
+struct Outcome {
+    facts: Vec<Fact>,
+    alternatives: Vec<Vec<(String, Vec<usize>)>>, // Other derivations found for each fact
+    proof: Option<usize>,        // Fact that proves the target or its negation
+    verdict: &'static str,       // "Valid", "Invalid" or "Insufficient Information"
+    stats: Stats,
+}
+
+/// Forward chaining with inference rules to derive new facts until:
 ///   - The target is found (Valid)
 ///   - The negation of target is found (Invalid)
-///   - No more facts can be derived (Insufficient Information)
-fn solve(premises: Vec<Expr>, deduction: Expr) {
-    let mut facts: Vec<Fact> = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();  // Prevent duplicate facts
+///   - No more facts can be derived or a limit is hit (Insufficient Information)
+///
+/// Expressions are hash-consed so duplicate checks and rule matching compare ids,
+/// and the fact base is indexed so each rule only visits candidate partners.
+/// With `saturate` the search keeps going after the target is found, so every
+/// alternative derivation within the limits is recorded (for shortest proofs).
+fn forward_chain(premises: Vec<Expr>, target: &Expr, limits: &Limits, lemmas: &[lemma::Lemma], saturate: bool) -> Outcome {
+    let start = Instant::now();
+    let mut interner = kb::Interner::default();
+    let mut base = kb::FactBase::default();
+    let mut alternatives: Vec<Vec<(String, Vec<usize>)>> = Vec::new();
+    let mut stats = Stats::default();
 
     // Initialize knowledge base with premises
-    for (i, p) in premises.into_iter().enumerate() {
-        seen.insert(format!("{:?}", p));
-        facts.push(Fact { expr: p, source: Source::Premise, id: i });
+    for p in &premises {
+        let key = interner.intern(p);
+        if base.add(&interner, key, Source::Premise).is_some() {
+            stats.premises += 1;
+            alternatives.push(Vec::new());
+        }
     }
 
-    let target = deduction;
-    let neg_target = negate(&target);
+    let target_key = interner.intern(target);
+    let neg_target_key = interner.negate(target_key);
+    let mut quantifiers = fol::Quantifiers::new(&base.facts, target);
     let mut result = (None, "Insufficient Information");
     let mut idx = 0;
 
     // Forward chaining: process each fact and derive new ones
-    while idx < facts.len() && facts.len() < 10000 {
-        let cur = facts[idx].clone();
+    while idx < base.facts.len() {
+        if base.facts.len() >= limits.max_facts { stats.limit = Some("fact"); break; }
+        if stats.steps >= limits.max_steps { stats.limit = Some("step"); break; }
+        if limits.timeout.is_some_and(|t| start.elapsed() >= t) { stats.limit = Some("time"); break; }
+        stats.steps += 1;
 
         // Check if we've reached our goal
-        if cur.expr == target { result = (Some(idx), "Valid"); break; }
-        if cur.expr == neg_target { result = (Some(idx), "Invalid"); break; }
+        let key = base.facts[idx].key;
+        if result.0.is_none() {
+            if key == target_key { result = (Some(idx), "Valid"); }
+            if key == neg_target_key { result = (Some(idx), "Invalid"); }
+        }
+        if result.0.is_some() && !saturate { break; }
 
         let mut new = Vec::new();
-        
-        // Apply single-fact inference rules
-        // Simplification: (A * B) => A, (A * B) => B
-        if let Expr::And(a, b) = &cur.expr {
-            new.push((*a.clone(), "Simplification".to_string(), vec![cur.id]));
-            new.push((*b.clone(), "Simplification".to_string(), vec![cur.id]));
-        }
-        
-        // Apply two-fact inference rules with all other facts
-        for j in 0..facts.len() {
-            if j == idx { continue; }
-            let other = &facts[j];
-            // Try both orderings since some rules are order-dependent
-            apply_rules(&cur, other, &facts, &mut new);
-            apply_rules(other, &cur, &facts, &mut new);
+        apply_rules(&base, &mut interner, lemmas, idx, &mut new);
+
+        // Apply quantifier rules (instantiation, generalization, negation)
+        let mut quantified = Vec::new();
+        quantifiers.apply(&base.facts[idx], &base, &mut quantified);
+        for (expr, rule, parents) in quantified {
+            new.push((interner.intern(&expr), rule, parents));
         }
 
         // Add newly derived facts to knowledge base
-        for (expr, rule, parents) in new {
-            let key = format!("{:?}", expr);
-            if !seen.contains(&key) {
-                seen.insert(key);
-                let nid = facts.len();
-                facts.push(Fact { 
-                    expr: expr.clone(), 
-                    source: Source::Derived { rule, parents }, 
-                    id: nid 
-                });
-                
-                // Check immediately if new fact proves our goal
-                if expr == target { result = (Some(nid), "Valid"); break; }
-                if expr == neg_target { result = (Some(nid), "Invalid"); break; }
+        for (key, rule, parents) in new {
+            if let Some(existing) = base.find(key) {
+                // Keep the alternative derivation unless it is the one already recorded
+                stats.duplicates += 1;
+                let known = match &base.facts[existing].source {
+                    Source::Derived { rule: r, parents: ps } => r == rule && *ps == parents,
+                    Source::Premise => true,
+                };
+                let alts = &mut alternatives[existing];
+                if !known && !parents.contains(&existing) && !alts.iter().any(|(r, ps)| r == rule && *ps == parents) {
+                    alts.push((rule.to_string(), parents));
+                }
+                continue;
+            }
+            let nid = base.facts.len();
+            base.add(&interner, key, Source::Derived { rule: rule.to_string(), parents });
+            alternatives.push(Vec::new());
+            *stats.rules.entry(rule.to_string()).or_default() += 1;
+
+            // Check immediately if new fact proves our goal
+            if result.0.is_none() {
+                if key == target_key { result = (Some(nid), "Valid"); }
+                if key == neg_target_key { result = (Some(nid), "Invalid"); }
             }
+            if result.0.is_some() && !saturate { break; }
         }
-        
-        if result.0.is_some() { break; }
+
+        if result.0.is_some() && !saturate { break; }
         idx += 1;
     }
 
+    stats.interned = interner.node_count();
+    stats.elapsed = start.elapsed();
+    Outcome { facts: base.facts, alternatives, proof: result.0, verdict: result.1, stats }
+}
+
+/// Forward chaining, through the linear-time Horn algorithm when every premise is
+/// a Horn clause (lemmas are only applied by the general algorithm)
+fn prove(premises: Vec<Expr>, target: &Expr, limits: &Limits, lemmas: &[lemma::Lemma], saturate: bool) -> Outcome {
+    if lemmas.is_empty() && horn::applies(&premises, target) {
+        horn::forward_chain(&premises, target, limits)
+    } else {
+        forward_chain(premises, target, limits, lemmas, saturate)
+    }
+}
+
+/// Main solver: attempts to prove or disprove the deduction from given premises
+/// The premises are first checked for consistency, since contradictory premises make
+/// any verdict vacuous, and (for at most analysis::DETAIL_LIMIT premises, or with
+/// --analyze) for redundancy and the minimal subset the deduction needs.
+/// With --shortest the fact base is saturated and searched for a proof with the
+/// fewest derived steps; with --minimize redundant steps are pruned from the proof.
+fn solve(premises: Vec<Expr>, deduction: Expr, opts: &Options) {
+    let detailed = opts.analyze || premises.len() <= analysis::DETAIL_LIMIT;
+    analysis::print_analysis(&premises, &analysis::analyze(&premises, &deduction, detailed));
+    let outcome = prove(premises, &deduction, &opts.limits, &opts.lemmas, opts.shortest);
+    if outcome.stats.horn {
+        println!("All premises are Horn clauses: using linear-time propagation\n");
+    }
+
     // Output results
-    println!("Determination: {}", result.1);
-    if let Some(id) = result.0 {
-        println!("\nProof Trace:");
-        print_proof(&facts, id);
+    println!("Determination: {}", outcome.verdict);
+    if let Some(id) = outcome.proof {
+        let found = proof::derived_steps(&outcome.facts, id);
+        let (heading, facts, target) = if opts.shortest {
+            match proof::shortest(&outcome.facts, &outcome.alternatives, id) {
+                Some(facts) => {
+                    (format!("shortest: {} derived steps, first found had {}", proof::derived_steps(&facts, id), found), facts, id)
+                }
+                None => {
+                    let facts = proof::minimize(&outcome.facts, &outcome.alternatives, id);
+                    (format!("search budget exhausted; minimized from {} to {} derived steps",
+                        found, proof::derived_steps(&facts, id)), facts, id)
+                }
+            }
+        } else if opts.minimize {
+            // Saturate over just the premises this proof uses, so the minimizer can
+            // choose among every derivation available to it
+            let mut used = Vec::new();
+            collect_deps(&outcome.facts, id, &mut used);
+            let used: Vec<Expr> = used.iter()
+                .filter(|&&i| matches!(outcome.facts[i].source, Source::Premise))
+                .map(|&i| outcome.facts[i].expr.clone())
+                .collect();
+            let local = forward_chain(used, &deduction, &opts.limits, &opts.lemmas, true);
+            let mut best = (proof::minimize(&outcome.facts, &outcome.alternatives, id), id);
+            if let Some(t) = local.proof {
+                let facts = proof::minimize(&local.facts, &local.alternatives, t);
+                if proof::derived_steps(&facts, t) < proof::derived_steps(&best.0, best.1) { best = (facts, t); }
+            }
+            let (facts, target) = best;
+            (format!("minimized from {} to {} derived steps", found, proof::derived_steps(&facts, target)), facts, target)
+        } else {
+            (String::new(), outcome.facts.clone(), id)
+        };
+
+        if heading.is_empty() {
+            println!("\nProof Trace:");
+        } else {
+            println!("\nProof Trace ({}):", heading);
+        }
+        print_proof(&facts, target);
+        if let Some(format) = opts.proof_format {
+            let text = export::render(&facts, target, format);
+            match &opts.proof_file {
+                Some(path) => match fs::write(path, text) {
+                    Ok(()) => println!("\nWrote proof to {}", path),
+                    Err(e) => println!("\nCould not write {}: {}", path, e),
+                },
+                None => print!("\n{}", text),
+            }
+        }
+        if let Some(name) = &opts.save_lemma {
+            save_lemma(&facts, target, outcome.verdict, name, opts);
+        }
+    }
+    outcome.stats.print(outcome.facts.len());
+}
+
+/// Save the proven argument, restricted to the premises its proof uses, as a lemma
+fn save_lemma(facts: &[Fact], target: usize, verdict: &str, name: &str, opts: &Options) {
+    let Some(path) = &opts.lemma_file else { return; };
+    if verdict != "Valid" {
+        println!("\nNot saving lemma {}: only valid arguments can become lemmas", name);
+        return;
+    }
+    let mut used = Vec::new();
+    collect_deps(facts, target, &mut used);
+    let premises: Vec<Expr> = used.iter()
+        .filter(|&&i| matches!(facts[i].source, Source::Premise))
+        .map(|&i| facts[i].expr.clone())
+        .collect();
+    let result = lemma::schema_from(&premises, &facts[target].expr)
+        .ok_or_else(|| format!("Not saving lemma {}: the proof uses no premises", name))
+        .and_then(|schema| lemma::Lemma::new(name, schema))
+        .and_then(|l| lemma::save(path, &l).map(|()| l));
+    match result {
+        Ok(l) => println!("\nSaved lemma {}: {} to {}", l.name, pretty::pretty(&l.schema, pretty::Style::Ascii), path),
+        Err(e) => println!("\n{}", e),
     }
 }
 
-/// Apply all two-fact inference rules between two facts
+/// A derived fact before it enters the knowledge base: expression, rule, parent facts
+/// (rule names borrow from the lemmas for lemma applications)
+type Derivation<'a> = (kb::ExprId, &'a str, Vec<usize>);
+
+/// Apply all inference rules that involve the fact at `idx`, looking up partner
+/// facts through the fact base indexes instead of scanning every fact
 /// Rules implemented:
+///   - Simplification: (A * B) => A, (A * B) => B
 ///   - Modus Ponens: (A > B), A => B
 ///   - Modus Tollens: (A > B), ~B => ~A
 ///   - Hypothetical Syllogism: (A > B), (B > C) => (A > C)
 ///   - Disjunctive Syllogism: (A + B), ~A => B
 ///   - Addition: A => (A + B) when (A + B) appears in an implication
 ///   - Conjunction: A, B => (A * B) when (A * B) appears in an implication
-fn apply_rules(p1: &Fact, p2: &Fact, all: &[Fact], out: &mut Vec<(Expr, String, Vec<usize>)>) {
-    let (e1, e2) = (&p1.expr, &p2.expr);
+///   - Lemmas: H1, ..., Hn => C for each loaded lemma (H1 * ... * Hn) > C whose
+///     hypotheses unify with known facts, one of them the fact at `idx`
+fn apply_rules<'a>(base: &kb::FactBase, interner: &mut kb::Interner, lemmas: &'a [lemma::Lemma], idx: usize,
+                   out: &mut Vec<Derivation<'a>>) {
+    use kb::Node;
+    let key = base.facts[idx].key;
+    let node = interner.node(key).clone();
+
+    // Simplification: (A * B) => A, (A * B) => B
+    if let Node::And(a, b) = node {
+        out.push((a, "Simplification", vec![idx]));
+        out.push((b, "Simplification", vec![idx]));
+    }
+
+    // The current fact as an implication (A > B)
+    if let Node::Implies(a, b) = node {
+        // Modus Ponens: A is known
+        if let Some(j) = base.find(a) {
+            out.push((b, "Modus Ponens", vec![idx, j]));
+        }
+
+        // Modus Tollens: ~B is known
+        let not_b = interner.negate(b);
+        if let Some(j) = base.find(not_b) {
+            let not_a = interner.negate(a);
+            out.push((not_a, "Modus Tollens", vec![idx, j]));
+        }
+
+        // Hypothetical Syllogism in both directions: (B > C) and (Z > A) are known
+        for &j in base.with_antecedent(b) {
+            if let Node::Implies(_, c) = *interner.node(base.facts[j].key) {
+                let ac = interner.node_id(Node::Implies(a, c));
+                out.push((ac, "Hypothetical Syllogism", vec![idx, j]));
+            }
+        }
+        for &j in base.with_consequent(a) {
+            if let Node::Implies(z, _) = *interner.node(base.facts[j].key) {
+                let zb = interner.node_id(Node::Implies(z, b));
+                out.push((zb, "Hypothetical Syllogism", vec![j, idx]));
+            }
+        }
+
+        // Addition: a disjunct of the antecedent (A1 + A2) is known
+        if let Node::Or(l, r) = *interner.node(a)
+            && let Some(j) = base.find(l).or_else(|| base.find(r)) {
+            out.push((a, "Addition", vec![j]));
+        }
 
-    // Modus Ponens: If we have (A > B) and A, derive B
-    if let Expr::Implies(a, b) = e1 {
-        if **a == *e2 { 
-            out.push((*b.clone(), "Modus Ponens".to_string(), vec![p1.id, p2.id])); 
+        // Conjunction: both conjuncts of the antecedent (A1 * A2) are known
+        if let Node::And(l, r) = *interner.node(a)
+            && let (Some(i), Some(j)) = (base.find(l), base.find(r))
+            && i != j {
+            out.push((a, "Conjunction", vec![i, j]));
         }
     }
 
-    // Modus Tollens: If we have (A > B) and ~B, derive ~A
-    if let Expr::Implies(a, b) = e1 {
-        if negate(b) == *e2 { 
-            out.push((negate(a), "Modus Tollens".to_string(), vec![p1.id, p2.id])); 
+    // The current fact as a disjunction (A + B): Disjunctive Syllogism with ~A or ~B
+    if let Node::Or(a, b) = node {
+        let not_a = interner.negate(a);
+        let not_b = interner.negate(b);
+        if let Some(j) = base.find(not_a) {
+            out.push((b, "Disjunctive Syllogism", vec![idx, j]));
+        } else if let Some(j) = base.find(not_b) {
+            out.push((a, "Disjunctive Syllogism", vec![idx, j]));
         }
     }
 
-    // Hypothetical Syllogism: If we have (A > B) and (B > C), derive (A > C)
-    if let (Expr::Implies(a, b), Expr::Implies(c, d)) = (e1, e2) {
-        if **b == **c { 
-            out.push((Expr::Implies(a.clone(), d.clone()), "Hypothetical Syllogism".to_string(), vec![p1.id, p2.id])); 
+    // The current fact X as the minor premise of another fact's rule
+    // Modus Ponens: (X > B) is known
+    for &j in base.with_antecedent(key) {
+        if let Node::Implies(_, b) = *interner.node(base.facts[j].key) {
+            out.push((b, "Modus Ponens", vec![j, idx]));
         }
     }
 
-    // Disjunctive Syllogism: If we have (A + B) and ~A, derive B
-    if let Expr::Or(a, b) = e1 {
-        if negate(a) == *e2 { 
-            out.push((*b.clone(), "Disjunctive Syllogism".to_string(), vec![p1.id, p2.id])); 
+    for n in interner.negated_by(key) {
+        // Modus Tollens: (A > B) is known with ~B == X
+        for &j in base.with_consequent(n) {
+            if let Node::Implies(a, _) = *interner.node(base.facts[j].key) {
+                let not_a = interner.negate(a);
+                out.push((not_a, "Modus Tollens", vec![j, idx]));
+            }
         }
-        else if negate(b) == *e2 { 
-            out.push((*a.clone(), "Disjunctive Syllogism".to_string(), vec![p1.id, p2.id])); 
+
+        // Disjunctive Syllogism: (A + B) is known with ~A == X or ~B == X
+        for &j in base.with_disjunct(n) {
+            if let Node::Or(a, b) = *interner.node(base.facts[j].key) {
+                if interner.negate(a) == key {
+                    out.push((b, "Disjunctive Syllogism", vec![j, idx]));
+                } else if interner.negate(b) == key {
+                    out.push((a, "Disjunctive Syllogism", vec![j, idx]));
+                }
+            }
         }
     }
 
-    // Addition: If we have A and see an implication with (A + B) as antecedent,
-    // derive (A + B) so Modus Ponens can fire
-    if let Expr::Implies(ant, _) = e2 {
-        if let Expr::Or(l, r) = ant.as_ref() {
-            if **l == *e1 || **r == *e1 { 
-                out.push((ant.as_ref().clone(), "Addition".to_string(), vec![p1.id])); 
+    // Addition: X is a disjunct of some implication's antecedent (X + B)
+    for &ant in base.or_antecedents(key) {
+        out.push((ant, "Addition", vec![idx]));
+    }
+
+    // Conjunction: X is a conjunct of some implication's antecedent (X * B), B known
+    for &ant in base.and_antecedents(key) {
+        if let Node::And(l, r) = *interner.node(ant) {
+            let other = if l == key { r } else { l };
+            if let Some(j) = base.find(other)
+                && j != idx {
+                out.push((ant, "Conjunction", vec![idx, j]));
             }
         }
     }
 
-    // Conjunction: If we have A and B, derive (A * B) but ONLY if it matches
-    // an implication's antecedent to avoid exponential explosion
-    let conj = Expr::And(Box::new(e1.clone()), Box::new(e2.clone()));
-    let conj_rev = Expr::And(Box::new(e2.clone()), Box::new(e1.clone()));
-    
-    for f in all {
-        if let Expr::Implies(ant, _) = &f.expr {
-            if let Expr::And(_, _) = ant.as_ref() {
-                if **ant == conj || **ant == conj_rev {
-                    out.push((ant.as_ref().clone(), "Conjunction".to_string(), vec![p1.id, p2.id]));
-                    return; // Only generate once
-                }
-            }
+    // Lemmas: instantiate the conclusion of every lemma whose hypotheses match
+    for lemma in lemmas {
+        for (e, parents) in lemma.instances(base, idx) {
+            out.push((interner.intern(&e), lemma.rule.as_str(), parents));
         }
     }
 }
@@ -333,7 +723,7 @@ fn print_proof(facts: &[Fact], target_id: usize) {
         let f = &facts[idx];
         match &f.source {
             Source::Premise => {
-                println!("Step {}: {} [Premise]", n + 1, f.expr);
+                println!("Step {}: {} [Premise]", n + 1, pretty::pretty(&f.expr, pretty::Style::Ascii));
             }
             Source::Derived { rule, parents } => {
                 // Map parent fact IDs to their step numbers in the proof
@@ -341,7 +731,7 @@ fn print_proof(facts: &[Fact], target_id: usize) {
                     .map(|&id| format!("Step {}", steps.iter().position(|&x| x == id).unwrap() + 1))
                     .collect();
                 println!("Step {}: {} [Derived from {} using {}]", 
-                    n + 1, f.expr, ps.join(", "), rule);
+                    n + 1, pretty::pretty(&f.expr, pretty::Style::Ascii), ps.join(", "), rule);
             }
         }
     }
@@ -351,12 +741,331 @@ fn print_proof(facts: &[Fact], target_id: usize) {
 // MAIN ENTRY POINT
 // ============================================================================
 
+/// Which engine decides the argument
+#[derive(Clone, Copy)]
+enum Engine {
+    Forward,     // Forward chaining with a proof trace (default)
+    Sat,         // Tseitin CNF + DPLL satisfiability check
+    Resolution,  // Resolution refutation with set of support
+    Tableau,     // Analytic tableau with tree rendering
+    Modal,       // Kripke countermodel search for a modal system
+}
+
+impl Engine {
+    /// Look an engine up by its command-line name
+    fn named(s: &str) -> Option<Engine> {
+        match s {
+            "forward" => Some(Engine::Forward),
+            "sat" => Some(Engine::Sat),
+            "resolution" => Some(Engine::Resolution),
+            "tableau" => Some(Engine::Tableau),
+            "modal" => Some(Engine::Modal),
+            _ => None,
+        }
+    }
+}
+
+/// Command-line options
+struct Options {
+    engine: Engine,
+    dimacs: Option<String>,         // Solve this DIMACS CNF file and exit
+    export_dimacs: Option<String>,  // Write CNF of premises * ~deduction to this file
+    normal_form: Option<normal::Form>, // Convert each input line instead of solving
+    dot: Option<String>,            // Write the tableau to this file as DOT
+    limits: Limits,                 // Forward-chaining resource limits
+    shortest: bool,                 // Search for a proof with the fewest steps
+    minimize: bool,                 // Drop redundant steps from the proof found
+    proof_format: Option<export::Format>, // Also render the proof as LaTeX, Markdown or JSON
+    proof_file: Option<String>,     // Write the rendered proof here instead of stdout
+    repl: bool,                     // Start the interactive prover
+    json: bool,                     // Answer JSON requests from stdin, one per line
+    batch: Option<String>,          // Run every problem in this file and check verdicts
+    simplify: bool,                 // Simplify each input line instead of solving
+    trace: bool,                    // Show the rewrite trace (--simplify) or subformula values (--eval)
+    pretty: bool,                   // Print each input line in every notation
+    lemma_file: Option<String>,     // Lemma library to load (and to save into)
+    save_lemma: Option<String>,     // Save a valid argument as a lemma with this name
+    lemmas: Vec<lemma::Lemma>,      // Lemmas loaded from `lemma_file`
+    system: modal::System,          // Modal system for the modal engine
+    eval: Option<manyvalued::Logic>, // Evaluate each input line in this many-valued logic
+    assign_file: Option<String>,    // Truth values of the atoms for --eval
+    three_valued: bool,             // Compare validity in K3 and Ł3 with classical validity
+    english: Option<String>,        // Read the argument in English with this dictionary
+    analyze: bool,                  // Check redundant and needed premises however many there are
+}
+
+/// Parse a non-negative count given on the command line
+fn parse_count(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|_| format!("Expected a number, found: {}", s))
+}
+
+/// Parse command-line flags:
+///   --engine forward|sat|resolution|tableau|modal  choose the proving engine
+///   --system k|t|s4|s5      modal system for the modal engine (default K)
+///   --dimacs <file>         solve a DIMACS CNF file directly
+///   --export-dimacs <file>  write the CNF of premises * ~deduction
+///   --normal-form nnf|cnf|dnf  print step-by-step conversions of each input line
+///   --dot <file>            write the tableau as Graphviz DOT (tableau engine)
+///   --max-facts <n>         stop forward chaining once n facts are known (default 10000)
+///   --max-steps <n>         stop forward chaining after processing n facts
+///   --timeout-ms <n>        stop forward chaining after n milliseconds
+///   --shortest              search for a proof with the fewest derived steps
+///   --minimize              remove redundant steps from the proof found
+///   --proof-format latex|markdown|json  also render the proof in this format
+///   --proof-file <file>     write the rendered proof to a file instead of stdout
+///   --repl                  work through a proof interactively
+///   --json                  answer one JSON request per stdin line with one JSON response
+///   --batch <file>          solve a problem file and compare with expected verdicts
+///   --simplify              simplify each input line algebraically
+///   --trace                 show every rewrite step with --simplify, or subformula values with --eval
+///   --pretty                print each input line in ASCII, Unicode and LaTeX notation
+///   --lemmas <file>         use the lemmas in this file as extra inference rules
+///   --save-lemma <name>     save a valid argument to the --lemmas file as a schema
+///   --eval kleene|lukasiewicz|fuzzy  evaluate each input line in a many-valued logic
+///   --assign <file>         truth values for --eval, one 'atom = value' per line
+///   --three-valued          compare validity in K3 and Ł3 with classical validity
+///   --english <dict>        read the argument as English sentences, using a dictionary
+///                           of 'formula = sentence | sentence' lines for atomic sentences
+///   --analyze               check redundant and needed premises even past 50 premises
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options {
+        engine: Engine::Forward,
+        dimacs: None,
+        export_dimacs: None,
+        normal_form: None,
+        dot: None,
+        limits: Limits::default(),
+        shortest: false,
+        minimize: false,
+        proof_format: None,
+        proof_file: None,
+        repl: false,
+        json: false,
+        batch: None,
+        simplify: false,
+        trace: false,
+        pretty: false,
+        lemma_file: None,
+        save_lemma: None,
+        lemmas: Vec::new(),
+        system: modal::System::K,
+        eval: None,
+        assign_file: None,
+        three_valued: false,
+        english: None,
+        analyze: false,
+    };
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        let mut value = || it.next().cloned().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--engine" => {
+                let name = value()?;
+                opts.engine = Engine::named(&name).ok_or(format!("Unknown engine: {}", name))?;
+            }
+            "--dimacs" => opts.dimacs = Some(value()?),
+            "--export-dimacs" => opts.export_dimacs = Some(value()?),
+            "--dot" => opts.dot = Some(value()?),
+            "--shortest" => opts.shortest = true,
+            "--minimize" => opts.minimize = true,
+            "--proof-file" => opts.proof_file = Some(value()?),
+            "--repl" => opts.repl = true,
+            "--json" => opts.json = true,
+            "--batch" => opts.batch = Some(value()?),
+            "--simplify" => opts.simplify = true,
+            "--trace" => opts.trace = true,
+            "--pretty" => opts.pretty = true,
+            "--system" => {
+                let name = value()?;
+                opts.system = modal::System::named(&name).ok_or(format!("Unknown modal system: {}", name))?;
+            }
+            "--eval" => {
+                let name = value()?;
+                opts.eval = Some(manyvalued::Logic::named(&name).ok_or(format!("Unknown logic: {}", name))?);
+            }
+            "--assign" => opts.assign_file = Some(value()?),
+            "--three-valued" => opts.three_valued = true,
+            "--english" => opts.english = Some(value()?),
+            "--analyze" => opts.analyze = true,
+            "--lemmas" => opts.lemma_file = Some(value()?),
+            "--save-lemma" => opts.save_lemma = Some(value()?),
+            "--proof-format" => {
+                opts.proof_format = Some(match value()?.as_str() {
+                    "latex" => export::Format::Latex,
+                    "markdown" => export::Format::Markdown,
+                    "json" => export::Format::Json,
+                    other => return Err(format!("Unknown proof format: {}", other)),
+                });
+            }
+            "--max-facts" => opts.limits.max_facts = parse_count(&value()?)?,
+            "--max-steps" => opts.limits.max_steps = parse_count(&value()?)?,
+            "--timeout-ms" => opts.limits.timeout = Some(Duration::from_millis(parse_count(&value()?)? as u64)),
+            "--normal-form" => {
+                opts.normal_form = Some(match value()?.as_str() {
+                    "nnf" => normal::Form::Nnf,
+                    "cnf" => normal::Form::Cnf,
+                    "dnf" => normal::Form::Dnf,
+                    other => return Err(format!("Unknown normal form: {}", other)),
+                });
+            }
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+    if opts.save_lemma.is_some() && opts.lemma_file.is_none() {
+        return Err("--save-lemma needs a --lemmas file to save into".to_string());
+    }
+    if opts.eval.is_some() && opts.assign_file.is_none() {
+        return Err("--eval needs an --assign file with the truth values".to_string());
+    }
+    Ok(opts)
+}
+
+/// Decide the argument with the engine chosen on the command line
+fn run_engine(premises: Vec<Expr>, d: Expr, opts: &Options) {
+    match opts.engine {
+        Engine::Forward => solve(premises, d, opts),
+        Engine::Sat => sat::decide(&premises, &d),
+        Engine::Resolution => resolution::solve(&premises, &d),
+        Engine::Modal => modal::solve(&premises, &d, opts.system),
+        Engine::Tableau => {
+            let t = tableau::solve(&premises, &d);
+            if let Some(path) = &opts.dot {
+                match fs::write(path, t.to_dot()) {
+                    Ok(()) => println!("\nWrote tableau DOT to {}", path),
+                    Err(e) => println!("\nCould not write {}: {}", path, e),
+                }
+            }
+        }
+    }
+}
+
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut opts = match parse_args(&args) {
+        Ok(o) => o,
+        Err(e) => { println!("{}", e); return; }
+    };
+
+    // A lemma file that does not exist yet is fine when we are about to save into it
+    if let Some(path) = &opts.lemma_file
+        && (opts.save_lemma.is_none() || fs::metadata(path).is_ok()) {
+        match lemma::load(path) {
+            Ok(lemmas) => opts.lemmas = lemmas,
+            Err(e) => { println!("{}", e); return; }
+        }
+    }
+
+    if let Some(path) = &opts.dimacs {
+        match fs::read_to_string(path) {
+            Ok(text) => sat::solve_dimacs(&text),
+            Err(e) => println!("Could not read {}: {}", path, e),
+        }
+        return;
+    }
+
+    if let Some(path) = &opts.batch {
+        // Non-zero exit status lets scripts treat a mismatch as a failed regression
+        if !batch::run(path, &opts) { process::exit(1); }
+        return;
+    }
+
+    if opts.repl {
+        repl::run(&opts);
+        return;
+    }
+
+    if opts.json {
+        protocol::run(&opts);
+        return;
+    }
+
     let stdin = io::stdin();
+
+    if let Some(path) = &opts.english {
+        let dict = match english::load(path) {
+            Ok(d) => d,
+            Err(e) => { println!("{}", e); return; }
+        };
+        println!("Enter the argument in English, one or more sentences per line, ending with EOF.");
+        println!("Begin the conclusion with 'Therefore', 'So' or 'Hence'.");
+        let mut text = String::new();
+        for line in stdin.lock().lines().map_while(Result::ok) {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        match english::translate(&text, &dict) {
+            Ok(arg) => {
+                english::print_translation(&arg);
+                let premises = arg.premises.into_iter().map(|(_, e)| e).collect();
+                run_engine(premises, arg.conclusion.1, &opts);
+            }
+            Err(e) => println!("Translation error: {}", e),
+        }
+        return;
+    }
+
+    if let Some(form) = opts.normal_form {
+        println!("Enter expressions (one per line) to convert.");
+        for line in stdin.lock().lines().map_while(Result::ok) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() { continue; }
+            match Parser::new(trimmed).parse_all() {
+                Ok(e) => { println!(); normal::print_conversion(&e, form); }
+                Err(err) => println!("Parse error: {}", err),
+            }
+        }
+        return;
+    }
+
+    if opts.pretty {
+        println!("Enter expressions (one per line) to print.");
+        for line in stdin.lock().lines().map_while(Result::ok) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() { continue; }
+            match Parser::new(trimmed).parse_all() {
+                Ok(e) => { println!(); pretty::print_styles(&e); }
+                Err(err) => println!("Parse error: {}", err),
+            }
+        }
+        return;
+    }
+
+    if let (Some(logic), Some(path)) = (opts.eval, &opts.assign_file) {
+        let assign = match manyvalued::load(path, logic) {
+            Ok(a) => a,
+            Err(e) => { println!("{}", e); return; }
+        };
+        println!("Enter expressions (one per line) to evaluate.");
+        for line in stdin.lock().lines().map_while(Result::ok) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() { continue; }
+            match Parser::new(trimmed).parse_all() {
+                Ok(e) => { println!(); manyvalued::print_eval(&e, logic, &assign, opts.trace); }
+                Err(err) => println!("Parse error: {}", err),
+            }
+        }
+        return;
+    }
+
+    if opts.simplify {
+        println!("Enter expressions (one per line) to simplify.");
+        for line in stdin.lock().lines().map_while(Result::ok) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() { continue; }
+            match Parser::new(trimmed).parse_all() {
+                Ok(e) => { println!(); simplify::print_simplification(&e, opts.trace); }
+                Err(err) => println!("Parse error: {}", err),
+            }
+        }
+        return;
+    }
+
     let mut premises = Vec::new();
     let mut deduction = None;
 
     println!("Enter premises (one per line). Format: ~ (Not), * (And), + (Or), > (Implies).");
+    println!("First-order: P(x, y) (Predicate), forall x. A / exists x. A (also written with ∀ / ∃).");
+    println!("Modal: []A (Necessarily), <>A (Possibly) (also written with □ / ◇).");
     println!("End input with 'R' followed by deduction (e.g. 'R j').");
 
     // Read premises and deduction from stdin
@@ -366,18 +1075,74 @@ fn main() {
 
         if let Some(content) = trimmed.strip_prefix('R') {
             // Line starting with 'R' indicates the deduction to prove
-            if let Ok(e) = Parser::new(content.trim()).parse_expr() {
-                deduction = Some(e);
-                break;
+            match Parser::new(content.trim()).parse_all() {
+                Ok(e) => { deduction = Some(e); break; }
+                Err(err) => println!("Parse error in deduction: {}", err),
+            }
+        } else {
+            match Parser::new(trimmed).parse_all() {
+                Ok(e) => premises.push(e),
+                Err(err) => println!("Parse error (premise skipped): {}", err),
             }
-        } else if let Ok(e) = Parser::new(trimmed).parse_expr() {
-            premises.push(e);
         }
     }
 
-    if let Some(d) = deduction {
-        solve(premises, d);
-    } else {
+    let Some(d) = deduction else {
         println!("No deduction found.");
+        return;
+    };
+
+    if let Some(path) = &opts.export_dimacs {
+        let mut all = premises.clone();
+        all.push(negate(&d));
+        match fs::write(path, cnf::write_dimacs(&cnf::tseitin(&all))) {
+            Ok(()) => println!("Wrote CNF of premises * ~deduction to {}", path),
+            Err(e) => println!("Could not write {}: {}", path, e),
+        }
+    }
+
+    if opts.three_valued {
+        manyvalued::compare(&premises, &d);
+        return;
     }
-}
\ No newline at end of file
+
+    run_engine(premises, d, &opts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(premises: &[&str], goal: &str, limits: &Limits) -> Outcome {
+        let premises: Vec<Expr> = premises.iter().map(|p| Parser::new(p).parse_all().unwrap()).collect();
+        prove(premises, &Parser::new(goal).parse_all().unwrap(), limits, &[], false)
+    }
+
+    #[test]
+    fn forward_verdicts_follow_the_convention() {
+        let valid = run(&["p > q", "p"], "q", &Limits::default());
+        assert_eq!(valid.verdict, "Valid");
+        assert!(valid.stats.horn && valid.proof.is_some());
+
+        let invalid = run(&["p > q", "~q"], "p", &Limits::default());
+        assert_eq!(invalid.verdict, "Invalid");
+        assert!(!invalid.stats.horn);
+
+        let open = run(&["p"], "q", &Limits::default());
+        assert_eq!(open.verdict, "Insufficient Information");
+        assert!(open.proof.is_none());
+    }
+
+    #[test]
+    fn horn_path_stops_at_the_limits() {
+        let chain: Vec<String> = (0..100).map(|i| format!("p{} > p{}", i, i + 1)).chain(["p0".to_string()]).collect();
+        let chain: Vec<&str> = chain.iter().map(String::as_str).collect();
+        assert_eq!(run(&chain, "p100", &Limits::default()).verdict, "Valid");
+
+        let limits = Limits { max_steps: 10, ..Limits::default() };
+        let stopped = run(&chain, "p100", &limits);
+        assert!(stopped.stats.horn);
+        assert_eq!(stopped.verdict, "Insufficient Information");
+        assert_eq!(stopped.stats.limit, Some("step"));
+    }
+}
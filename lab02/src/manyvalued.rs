@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::pretty::{pretty, Style};
+use crate::{Expr, Parser};
+
+// ============================================================================
+// MANY-VALUED SEMANTICS
+// ============================================================================
+
+/// Truth-functional semantics over [0, 1], where 1 is true and 0 is false
+/// All three read ~A as 1 - A, A * B as min(A, B) and A + B as max(A, B);
+/// they differ in the values allowed and in implication.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Logic {
+    Kleene,       // Strong Kleene: values 0, 1/2, 1 and A > B = max(1 - A, B)
+    Lukasiewicz,  // A > B = min(1, 1 - A + B); with values 0, 1/2, 1 this is Ł3
+    Fuzzy,        // Min/max (Zadeh) fuzzy logic: A > B = max(1 - A, B)
+}
+
+impl Logic {
+    pub fn name(self) -> &'static str {
+        match self {
+            Logic::Kleene => "strong Kleene",
+            Logic::Lukasiewicz => "Łukasiewicz",
+            Logic::Fuzzy => "min/max fuzzy",
+        }
+    }
+
+    pub fn named(s: &str) -> Option<Logic> {
+        match s.to_lowercase().as_str() {
+            "kleene" | "k3" => Some(Logic::Kleene),
+            "lukasiewicz" | "łukasiewicz" | "l3" => Some(Logic::Lukasiewicz),
+            "fuzzy" | "zadeh" => Some(Logic::Fuzzy),
+            _ => None,
+        }
+    }
+
+    fn implies(self, a: f64, b: f64) -> f64 {
+        match self {
+            Logic::Kleene | Logic::Fuzzy => (1.0 - a).max(b),
+            Logic::Lukasiewicz => (1.0 - a + b).min(1.0),
+        }
+    }
+
+    /// Three-valued logics print T, U and F; fuzzy values print as numbers
+    fn show(self, v: f64) -> String {
+        if self == Logic::Fuzzy { return number(v); }
+        match v {
+            1.0 => "T".to_string(),
+            0.0 => "F".to_string(),
+            0.5 => "U".to_string(),
+            _ => number(v),
+        }
+    }
+}
+
+fn number(v: f64) -> String {
+    let s = format!("{:.4}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Truth value of every atom, keyed by its printed form; predicates, quantified
+/// and modal formulas are atoms, as in the truth-table check of `simplify`
+pub type Assignment = BTreeMap<String, f64>;
+
+/// Value of `e`, looking atoms up with `atom`; with `trace`, every compound
+/// subformula is recorded with its value, innermost first
+fn value(e: &Expr, logic: Logic, atom: &dyn Fn(&Expr) -> Result<f64, String>,
+         mut trace: Option<&mut Vec<(String, f64)>>) -> Result<f64, String> {
+    let v = match e {
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+            return atom(e);
+        }
+        Expr::Not(a) => 1.0 - value(a, logic, atom, trace.as_deref_mut())?,
+        Expr::And(a, b) => value(a, logic, atom, trace.as_deref_mut())?.min(value(b, logic, atom, trace.as_deref_mut())?),
+        Expr::Or(a, b) => value(a, logic, atom, trace.as_deref_mut())?.max(value(b, logic, atom, trace.as_deref_mut())?),
+        Expr::Implies(a, b) => {
+            let x = value(a, logic, atom, trace.as_deref_mut())?;
+            logic.implies(x, value(b, logic, atom, trace.as_deref_mut())?)
+        }
+    };
+    if let Some(t) = trace { t.push((pretty(e, Style::Ascii), v)); }
+    Ok(v)
+}
+
+/// Evaluate `e` under an assignment; unassigned atoms are an error
+pub fn eval(e: &Expr, logic: Logic, assign: &Assignment) -> Result<f64, String> {
+    let atom = |a: &Expr| assign.get(&a.to_string()).copied().ok_or(format!("No value assigned to {}", pretty(a, Style::Ascii)));
+    value(e, logic, &atom, None)
+}
+
+/// Print the value of `e`, and with `trace` the value of each subformula
+pub fn print_eval(e: &Expr, logic: Logic, assign: &Assignment, trace: bool) {
+    let atom = |a: &Expr| assign.get(&a.to_string()).copied().ok_or(format!("No value assigned to {}", pretty(a, Style::Ascii)));
+    let mut steps = Vec::new();
+    match value(e, logic, &atom, Some(&mut steps)) {
+        Ok(v) => {
+            if trace {
+                for (sub, v) in &steps { println!("  {} = {}", sub, logic.show(*v)); }
+            }
+            println!("Value ({}): {}", logic.name(), logic.show(v));
+        }
+        Err(err) => println!("Evaluation error: {}", err),
+    }
+}
+
+// ============================================================================
+// ASSIGNMENT FILES
+// ============================================================================
+
+/// Read an assignment file: one `atom = value` per line, '#' starts a comment line
+/// A value is T, F or U (1, 0 and 1/2), a number in [0, 1] or a fraction a/b;
+/// strong Kleene only accepts the three values T, U and F.
+pub fn load(path: &str, logic: Logic) -> Result<Assignment, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let mut assign = Assignment::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let at = |msg: String| format!("{}:{}: {}", path, n + 1, msg);
+        let (name, v) = line.split_once('=').ok_or_else(|| at("expected 'atom = value'".to_string()))?;
+        // Parse the name so that `P(a,b)` and `P(a, b)` denote the same atom
+        let atom = Parser::new(name.trim()).parse_all().map_err(at)?.to_string();
+        let v = truth_value(v.trim()).ok_or_else(|| at(format!("invalid truth value: {}", v.trim())))?;
+        if logic == Logic::Kleene && ![0.0, 0.5, 1.0].contains(&v) {
+            return Err(at(format!("strong Kleene logic only has the values T, U and F, not {}", number(v))));
+        }
+        if assign.insert(atom.clone(), v).is_some() {
+            return Err(at(format!("{} is assigned twice", atom)));
+        }
+    }
+    Ok(assign)
+}
+
+fn truth_value(s: &str) -> Option<f64> {
+    let v = match s.to_lowercase().as_str() {
+        "t" | "true" => 1.0,
+        "f" | "false" => 0.0,
+        "u" | "unknown" => 0.5,
+        s => match s.split_once('/') {
+            Some((a, b)) => {
+                let (a, b): (f64, f64) = (a.trim().parse().ok()?, b.trim().parse().ok()?);
+                if b == 0.0 { return None; }
+                a / b
+            }
+            None => s.parse().ok()?,
+        },
+    };
+    (0.0..=1.0).contains(&v).then_some(v)
+}
+
+// ============================================================================
+// THREE-VALUED VALIDITY
+// ============================================================================
+
+/// Largest number of atoms whose 3^n valuations are enumerated
+const MAX_ATOMS: usize = 12;
+
+fn atoms(e: &Expr, out: &mut Vec<String>) {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+            let name = e.to_string();
+            if !out.contains(&name) { out.push(name); }
+        }
+        Expr::Not(a) => atoms(a, out),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => { atoms(a, out); atoms(b, out); }
+    }
+}
+
+/// Outcome of checking an argument over every valuation
+enum Check {
+    Valid,
+    Counterexample(Vec<(String, f64)>),  // Premises all true, conclusion not true
+    TooLarge,
+}
+
+/// An argument is valid when every valuation from `values` that makes all
+/// premises true (the designated value 1) also makes the conclusion true
+fn check(premises: &[Expr], goal: &Expr, logic: Logic, values: &[f64]) -> Check {
+    let mut names = Vec::new();
+    for e in premises.iter().chain(std::iter::once(goal)) { atoms(e, &mut names); }
+    if names.len() > MAX_ATOMS { return Check::TooLarge; }
+
+    // Odometer over values^n, leftmost atom changing slowest
+    let mut row = vec![0; names.len()];
+    loop {
+        let assign: Assignment = names.iter().zip(&row).map(|(n, &i)| (n.clone(), values[i])).collect();
+        let truth = |e: &Expr| eval(e, logic, &assign).expect("every atom is assigned");
+        if premises.iter().all(|p| truth(p) == 1.0) && truth(goal) != 1.0 {
+            return Check::Counterexample(names.iter().cloned().zip(row.iter().map(|&i| values[i])).collect());
+        }
+        let Some(i) = row.iter().rposition(|&i| i + 1 < values.len()) else { return Check::Valid };
+        row[i] += 1;
+        for j in &mut row[i + 1..] { *j = 0; }
+    }
+}
+
+/// Check the argument classically and in the three-valued logics K3 and Ł3,
+/// pointing out classical inferences that a third value breaks
+pub fn compare(premises: &[Expr], goal: &Expr) {
+    let classical = check(premises, goal, Logic::Kleene, &[0.0, 1.0]);
+    let three = [0.0, 0.5, 1.0];
+    let results = [
+        ("Strong Kleene (K3)", Logic::Kleene, check(premises, goal, Logic::Kleene, &three)),
+        ("Łukasiewicz (Ł3)", Logic::Lukasiewicz, check(premises, goal, Logic::Lukasiewicz, &three)),
+    ];
+
+    let describe = |c: &Check, logic: Logic| match c {
+        Check::Valid => "Valid".to_string(),
+        Check::Counterexample(vals) => {
+            let vs: Vec<String> = vals.iter().map(|(n, v)| format!("{} = {}", n, logic.show(*v))).collect();
+            format!("Not valid (counterexample: {})", vs.join(", "))
+        }
+        Check::TooLarge => format!("Skipped (more than {} atoms)", MAX_ATOMS),
+    };
+    println!("Validity (designated value T; U is the third value):");
+    println!("  {:<20} {}", "Classical", describe(&classical, Logic::Kleene));
+    for (name, logic, result) in &results {
+        println!("  {:<20} {}", name, describe(result, *logic));
+    }
+
+    if matches!(classical, Check::Valid) {
+        let failing: Vec<&str> = results.iter()
+            .filter(|(_, _, r)| matches!(r, Check::Counterexample(_)))
+            .map(|(name, _, _)| *name)
+            .collect();
+        if failing.is_empty() {
+            println!("\nThe classical inference survives the third value.");
+        } else {
+            println!("\nClassically valid, but fails in {}: the counterexample uses U where classical logic has no gap.",
+                failing.join(" and "));
+        }
+    }
+}
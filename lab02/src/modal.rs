@@ -0,0 +1,280 @@
+use std::collections::BTreeSet;
+
+use crate::export::json_string;
+use crate::{negate, sat, Expr};
+
+// ============================================================================
+// MODAL SYSTEMS
+// ============================================================================
+
+/// Normal modal systems, by the frame conditions on accessibility
+#[derive(Clone, Copy, PartialEq)]
+pub enum System {
+    K,   // No conditions
+    T,   // Reflexive
+    S4,  // Reflexive and transitive
+    S5,  // Reflexive, symmetric and transitive
+}
+
+impl System {
+    pub fn name(self) -> &'static str {
+        match self { System::K => "K", System::T => "T", System::S4 => "S4", System::S5 => "S5" }
+    }
+
+    /// Look a system up by name, ignoring case
+    pub fn named(s: &str) -> Option<System> {
+        match s.to_lowercase().as_str() {
+            "k" => Some(System::K),
+            "t" => Some(System::T),
+            "s4" => Some(System::S4),
+            "s5" => Some(System::S5),
+            _ => None,
+        }
+    }
+}
+
+/// Largest frame tried by the model search; the SAT encoding grows with worlds^2
+const MAX_WORLDS: usize = 8;
+
+/// Modal subformulas and modal depth, used to size the search
+fn modal_parts(e: &Expr, subs: &mut BTreeSet<String>) -> usize {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) => 0,
+        Expr::Not(a) | Expr::Forall(_, a) | Expr::Exists(_, a) => modal_parts(a, subs),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => modal_parts(a, subs).max(modal_parts(b, subs)),
+        Expr::Necessarily(a) | Expr::Possibly(a) => {
+            subs.insert(e.to_string());
+            1 + modal_parts(a, subs)
+        }
+    }
+}
+
+/// Number of worlds that suffices for a countermodel if there is one at all
+///   - K and T: a tree of depth md(φ) branching at most once per modal subformula
+///   - S5: one world per modal subformula plus the actual world
+///   - S4: finite, but only bounded by 2^|subformulas|
+fn complete_bound(e: &Expr, system: System) -> usize {
+    let mut subs = BTreeSet::new();
+    let depth = modal_parts(e, &mut subs);
+    let branching = subs.len().max(1);
+    match system {
+        System::K | System::T => {
+            (0..=depth as u32).try_fold(0usize, |acc, d| acc.checked_add(branching.checked_pow(d)?)).unwrap_or(usize::MAX)
+        }
+        System::S5 => subs.len() + 1,
+        System::S4 => 1usize.checked_shl(subs.len() as u32).filter(|&n| n > 0).unwrap_or(usize::MAX),
+    }
+}
+
+// ============================================================================
+// KRIPKE MODELS AS SAT PROBLEMS
+// ============================================================================
+
+fn access(u: usize, v: usize) -> Expr {
+    Expr::Var(format!("R(w{}, w{})", u, v))
+}
+
+fn atom_at(e: &Expr, w: usize) -> Expr {
+    Expr::Var(format!("{}@w{}", e, w))
+}
+
+fn conj(items: Vec<Expr>) -> Expr {
+    items.into_iter().reduce(|a, b| Expr::And(Box::new(a), Box::new(b))).expect("at least one world")
+}
+
+fn disj(items: Vec<Expr>) -> Expr {
+    items.into_iter().reduce(|a, b| Expr::Or(Box::new(a), Box::new(b))).expect("at least one world")
+}
+
+/// Classical formula saying that `e` holds at world `w` of an `n`-world model
+///   □A at w: every v with R(w, v) has A at v
+///   ◇A at w: some v with R(w, v) has A at v
+///
+/// Predicates and quantified formulas are atoms with a truth value per world.
+fn at_world(e: &Expr, w: usize, n: usize) -> Expr {
+    let bin = |a: &Expr, b: &Expr| (Box::new(at_world(a, w, n)), Box::new(at_world(b, w, n)));
+    match e {
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) => atom_at(e, w),
+        Expr::Not(a) => Expr::Not(Box::new(at_world(a, w, n))),
+        Expr::And(a, b) => { let (x, y) = bin(a, b); Expr::And(x, y) }
+        Expr::Or(a, b) => { let (x, y) = bin(a, b); Expr::Or(x, y) }
+        Expr::Implies(a, b) => { let (x, y) = bin(a, b); Expr::Implies(x, y) }
+        Expr::Necessarily(a) => conj((0..n).map(|v| Expr::Implies(Box::new(access(w, v)), Box::new(at_world(a, v, n)))).collect()),
+        Expr::Possibly(a) => disj((0..n).map(|v| Expr::And(Box::new(access(w, v)), Box::new(at_world(a, v, n)))).collect()),
+    }
+}
+
+/// Frame conditions of the system over `n` worlds
+fn frame(system: System, n: usize) -> Vec<Expr> {
+    let mut out = Vec::new();
+    if system != System::K {
+        for w in 0..n { out.push(access(w, w)); }
+    }
+    if system == System::S5 {
+        for u in 0..n {
+            for v in 0..n {
+                out.push(Expr::Implies(Box::new(access(u, v)), Box::new(access(v, u))));
+            }
+        }
+    }
+    if matches!(system, System::S4 | System::S5) {
+        for u in 0..n {
+            for v in 0..n {
+                for x in 0..n {
+                    let both = Expr::And(Box::new(access(u, v)), Box::new(access(v, x)));
+                    out.push(Expr::Implies(Box::new(both), Box::new(access(u, x))));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// A Kripke countermodel read off a satisfying assignment
+pub struct Countermodel {
+    worlds: usize,
+    relation: Vec<(usize, usize)>,
+    valuation: Vec<Vec<(String, bool)>>,  // Per world: atom -> truth value
+}
+
+fn atoms(e: &Expr, out: &mut Vec<Expr>) {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) => if !out.contains(e) { out.push(e.clone()); },
+        Expr::Not(a) | Expr::Necessarily(a) | Expr::Possibly(a) => atoms(a, out),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => { atoms(a, out); atoms(b, out); }
+    }
+}
+
+/// Look for an `n`-world model of the system where `e` is false at world w0
+fn countermodel(e: &Expr, system: System, n: usize) -> Option<Countermodel> {
+    let mut constraints = frame(system, n);
+    constraints.push(negate(&at_world(e, 0, n)));
+    let model = sat::model(&constraints)?;
+    let value = |name: &str| model.iter().any(|(m, b)| m == name && *b);
+
+    let mut relation = Vec::new();
+    for u in 0..n {
+        for v in 0..n {
+            if value(&access(u, v).to_string()) { relation.push((u, v)); }
+        }
+    }
+    let mut names = Vec::new();
+    atoms(e, &mut names);
+    let valuation = (0..n)
+        .map(|w| names.iter().map(|a| (a.to_string(), value(&atom_at(a, w).to_string()))).collect())
+        .collect();
+    Some(Countermodel { worlds: n, relation, valuation })
+}
+
+impl Countermodel {
+    fn print(&self) {
+        let worlds: Vec<String> = (0..self.worlds).map(|w| format!("w{}", w)).collect();
+        println!("  Worlds: {} (formula false at w0)", worlds.join(", "));
+        let pairs: Vec<String> = self.relation.iter().map(|(u, v)| format!("w{} -> w{}", u, v)).collect();
+        println!("  Accessibility: {}", if pairs.is_empty() { "(none)".to_string() } else { pairs.join(", ") });
+        println!("  Valuation:");
+        for (w, vals) in self.valuation.iter().enumerate() {
+            let vs: Vec<String> = vals.iter().map(|(a, b)| format!("{} = {}", a, if *b { "T" } else { "F" })).collect();
+            println!("    w{}: {}", w, vs.join(", "));
+        }
+    }
+
+    /// Worlds, accessibility pairs and per-world valuations as a JSON object
+    pub fn to_json(&self) -> String {
+        let pairs: Vec<String> = self.relation.iter().map(|(u, v)| format!("[{}, {}]", u, v)).collect();
+        let valuation: Vec<String> = self.valuation.iter()
+            .map(|vals| {
+                let vs: Vec<String> = vals.iter().map(|(a, b)| format!("{}: {}", json_string(a), b)).collect();
+                format!("{{{}}}", vs.join(", "))
+            })
+            .collect();
+        format!("{{\"worlds\": {}, \"accessibility\": [{}], \"valuation\": [{}]}}",
+            self.worlds, pairs.join(", "), valuation.join(", "))
+    }
+}
+
+// ============================================================================
+// MODAL PROVER
+// ============================================================================
+
+/// Result of searching frames of increasing size for a countermodel
+pub enum Search {
+    Valid(usize),                 // None up to this size, which suffices for the system
+    Countermodel(Countermodel),
+    Bounded(usize, usize),        // None up to MAX_WORLDS; a complete search needs the second size
+}
+
+/// Frames of 1, 2, ... worlds are encoded as SAT problems; the first model found
+/// is the smallest countermodel
+fn search(formula: &Expr, system: System) -> Search {
+    let bound = complete_bound(formula, system);
+    let limit = bound.min(MAX_WORLDS);
+    for n in 1..=limit {
+        if let Some(model) = countermodel(formula, system, n) { return Search::Countermodel(model); }
+    }
+    if limit == bound { Search::Valid(limit) } else { Search::Bounded(limit, bound) }
+}
+
+/// The argument P1, ..., Pn / C is read as the formula (P1 * ... * Pn) > C
+fn argument(premises: &[Expr], goal: &Expr) -> Expr {
+    premises.iter().cloned()
+        .reduce(|a, b| Expr::And(Box::new(a), Box::new(b)))
+        .map_or(goal.clone(), |p| Expr::Implies(Box::new(p), Box::new(goal.clone())))
+}
+
+/// Verdict in the repository's terms plus the search behind it
+///   - no countermodel to premises > target       => Valid
+///   - no countermodel to premises > ~target      => Invalid (the negation follows)
+///   - otherwise                                  => Insufficient Information
+pub fn decide(premises: &[Expr], target: &Expr, system: System) -> (&'static str, Search) {
+    match search(&argument(premises, target), system) {
+        Search::Countermodel(model) => {
+            let verdict = match search(&argument(premises, &negate(target)), system) {
+                Search::Valid(_) => "Invalid",
+                _ => "Insufficient Information",
+            };
+            (verdict, Search::Countermodel(model))
+        }
+        Search::Valid(n) => ("Valid", Search::Valid(n)),
+        bounded => ("Insufficient Information", bounded),
+    }
+}
+
+/// Decide the argument in a modal system and print the countermodel if there is one
+pub fn solve(premises: &[Expr], target: &Expr, system: System) {
+    let (verdict, result) = decide(premises, target, system);
+    println!("Determination: {} (in {})", verdict, system.name());
+    match result {
+        Search::Valid(n) => {
+            println!("\nNo countermodel with up to {} world{}, which suffices for {}", n, if n == 1 { "" } else { "s" }, system.name());
+        }
+        Search::Countermodel(model) => {
+            println!("\nCountermodel ({} world{}):", model.worlds, if model.worlds == 1 { "" } else { "s" });
+            model.print();
+        }
+        Search::Bounded(limit, bound) => {
+            println!("\nNo countermodel with up to {} worlds (a complete search in {} would need {})", limit, system.name(), bound);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    fn check(premises: &[&str], goal: &str, system: System) -> &'static str {
+        let premises: Vec<Expr> = premises.iter().map(|p| Parser::new(p).parse_all().unwrap()).collect();
+        decide(&premises, &Parser::new(goal).parse_all().unwrap(), system).0
+    }
+
+    #[test]
+    fn verdicts_follow_the_convention() {
+        assert_eq!(check(&["[](p > q)", "[]p"], "[]q", System::K), "Valid");
+        assert_eq!(check(&["[]~p"], "<>p", System::K), "Invalid");
+        assert_eq!(check(&["<>p"], "[]p", System::S5), "Insufficient Information");
+        // reflexivity is what makes []p > p hold
+        assert_eq!(check(&["[]p"], "p", System::K), "Insufficient Information");
+        assert_eq!(check(&["[]p"], "p", System::T), "Valid");
+    }
+}
@@ -0,0 +1,132 @@
+use crate::pretty::{pretty, Style};
+use crate::Expr;
+
+// ============================================================================
+// NORMAL FORMS
+// ============================================================================
+
+/// Target normal form for a conversion
+#[derive(Clone, Copy)]
+pub enum Form {
+    Nnf,  // Negation normal form: only ~, *, + with ~ applied to variables
+    Cnf,  // Conjunction of disjunctions of literals
+    Dnf,  // Disjunction of conjunctions of literals
+}
+
+/// One step of a conversion: the whole expression after applying a law
+pub struct Step {
+    pub expr: Expr,
+    pub law: &'static str,
+}
+
+/// Equivalence laws used by the conversions, applied one occurrence at a time
+#[derive(Clone, Copy)]
+enum Law {
+    ImplicationElimination,  // (A > B) == (~A + B)
+    DoubleNegation,          // ~~A == A
+    DeMorgan,                // ~(A * B) == (~A + ~B), ~(A + B) == (~A * ~B)
+    DistributeOr,            // (A + (B * C)) == ((A + B) * (A + C))
+    DistributeAnd,           // (A * (B + C)) == ((A * B) + (A * C))
+}
+
+impl Law {
+    fn name(self) -> &'static str {
+        match self {
+            Law::ImplicationElimination => "Implication Elimination",
+            Law::DoubleNegation => "Double Negation",
+            Law::DeMorgan => "De Morgan",
+            Law::DistributeOr => "Distribution of + over *",
+            Law::DistributeAnd => "Distribution of * over +",
+        }
+    }
+
+    /// Try to apply this law at the root of `e`
+    fn apply(self, e: &Expr) -> Option<Expr> {
+        let not = |x: &Expr| Expr::Not(Box::new(x.clone()));
+        let and = |x: Expr, y: Expr| Expr::And(Box::new(x), Box::new(y));
+        let or = |x: Expr, y: Expr| Expr::Or(Box::new(x), Box::new(y));
+
+        match (self, e) {
+            (Law::ImplicationElimination, Expr::Implies(a, b)) => Some(or(not(a), *b.clone())),
+            (Law::DoubleNegation, Expr::Not(inner)) => match inner.as_ref() {
+                Expr::Not(a) => Some(*a.clone()),
+                _ => None,
+            },
+            (Law::DeMorgan, Expr::Not(inner)) => match inner.as_ref() {
+                Expr::And(a, b) => Some(or(not(a), not(b))),
+                Expr::Or(a, b) => Some(and(not(a), not(b))),
+                _ => None,
+            },
+            (Law::DistributeOr, Expr::Or(a, b)) => match (a.as_ref(), b.as_ref()) {
+                (_, Expr::And(b1, b2)) => Some(and(or(*a.clone(), *b1.clone()), or(*a.clone(), *b2.clone()))),
+                (Expr::And(a1, a2), _) => Some(and(or(*a1.clone(), *b.clone()), or(*a2.clone(), *b.clone()))),
+                _ => None,
+            },
+            (Law::DistributeAnd, Expr::And(a, b)) => match (a.as_ref(), b.as_ref()) {
+                (_, Expr::Or(b1, b2)) => Some(or(and(*a.clone(), *b1.clone()), and(*a.clone(), *b2.clone()))),
+                (Expr::Or(a1, a2), _) => Some(or(and(*a1.clone(), *b.clone()), and(*a2.clone(), *b.clone()))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Rewrite the first occurrence (outermost, leftmost) where any of `laws` applies
+fn rewrite_once(e: &Expr, laws: &[Law]) -> Option<(Expr, Law)> {
+    for &law in laws {
+        if let Some(r) = law.apply(e) { return Some((r, law)); }
+    }
+
+    match e {
+        // Quantified and modal formulas are treated as atoms by the propositional laws
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => None,
+        Expr::Not(a) => rewrite_once(a, laws).map(|(r, l)| (Expr::Not(Box::new(r)), l)),
+        Expr::And(a, b) | Expr::Or(a, b) | Expr::Implies(a, b) => {
+            let rebuild = |x: Expr, y: Expr| match e {
+                Expr::And(..) => Expr::And(Box::new(x), Box::new(y)),
+                Expr::Or(..) => Expr::Or(Box::new(x), Box::new(y)),
+                _ => Expr::Implies(Box::new(x), Box::new(y)),
+            };
+            if let Some((r, l)) = rewrite_once(a, laws) {
+                return Some((rebuild(r, *b.clone()), l));
+            }
+            rewrite_once(b, laws).map(|(r, l)| (rebuild(*a.clone(), r), l))
+        }
+    }
+}
+
+/// Apply `laws` until none fires, recording every intermediate expression
+fn saturate(mut e: Expr, laws: &[Law], steps: &mut Vec<Step>) -> Expr {
+    while let Some((next, law)) = rewrite_once(&e, laws) {
+        steps.push(Step { expr: next.clone(), law: law.name() });
+        e = next;
+    }
+    e
+}
+
+/// Convert an expression to the requested normal form
+/// Returns the sequence of steps; the last step (or the input, if empty) is the result.
+pub fn convert(e: &Expr, form: Form) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let nnf = saturate(e.clone(), &[Law::ImplicationElimination], &mut steps);
+    let nnf = saturate(nnf, &[Law::DoubleNegation, Law::DeMorgan], &mut steps);
+    match form {
+        Form::Nnf => {}
+        Form::Cnf => { saturate(nnf, &[Law::DistributeOr], &mut steps); }
+        Form::Dnf => { saturate(nnf, &[Law::DistributeAnd], &mut steps); }
+    }
+    steps
+}
+
+/// Print a conversion in the same step format as `print_proof`
+pub fn print_conversion(e: &Expr, form: Form) {
+    println!("Step 1: {} [Given]", pretty(e, Style::Ascii));
+    let steps = convert(e, form);
+    for (n, s) in steps.iter().enumerate() {
+        println!("Step {}: {} [{}]", n + 2, pretty(&s.expr, Style::Ascii), s.law);
+    }
+    let result = steps.last().map_or(e, |s| &s.expr);
+    let name = match form { Form::Nnf => "NNF", Form::Cnf => "CNF", Form::Dnf => "DNF" };
+    println!("{}: {}", name, pretty(result, Style::Ascii));
+}
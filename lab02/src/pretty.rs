@@ -0,0 +1,94 @@
+use crate::{Expr, Parser};
+
+// ============================================================================
+// PRECEDENCE-AWARE PRINTING
+// ============================================================================
+
+/// Notation for the printed operators
+#[derive(Clone, Copy)]
+pub enum Style {
+    Ascii,    // ~ * + > [] <>  forall x. / exists x.
+    Unicode,  // ¬ ∧ ∨ → □ ◇  ∀x. / ∃x.
+    Latex,    // \lnot \land \lor \to \Box \Diamond  \forall x\, / \exists x\,
+}
+
+/// Binding strength, loosest first; mirrors the levels of `Parser`
+const IMPLIES: u8 = 1;  // Right-associative
+const OR: u8 = 2;       // Left-associative
+const AND: u8 = 3;      // Left-associative
+const PREFIX: u8 = 4;   // Negation, modal operators and quantifiers
+const ATOM: u8 = 5;
+
+struct Symbols {
+    not: &'static str,
+    and: &'static str,
+    or: &'static str,
+    implies: &'static str,
+    necessarily: &'static str,
+    possibly: &'static str,
+}
+
+fn symbols(style: Style) -> Symbols {
+    match style {
+        Style::Ascii => Symbols { not: "~", and: " * ", or: " + ", implies: " > ", necessarily: "[]", possibly: "<>" },
+        Style::Unicode => Symbols { not: "¬", and: " ∧ ", or: " ∨ ", implies: " → ", necessarily: "□", possibly: "◇" },
+        // The space after \lnot keeps it from running into a following name
+        Style::Latex => Symbols {
+            not: "\\lnot ", and: " \\land ", or: " \\lor ", implies: " \\to ",
+            necessarily: "\\Box ", possibly: "\\Diamond ",
+        },
+    }
+}
+
+fn quantifier(style: Style, universal: bool, var: &str) -> String {
+    match (style, universal) {
+        (Style::Ascii, true) => format!("forall {}. ", var),
+        (Style::Ascii, false) => format!("exists {}. ", var),
+        (Style::Unicode, true) => format!("∀{}. ", var),
+        (Style::Unicode, false) => format!("∃{}. ", var),
+        (Style::Latex, true) => format!("\\forall {}\\, ", var),
+        (Style::Latex, false) => format!("\\exists {}\\, ", var),
+    }
+}
+
+/// Print `e` and report the binding strength of its main connective
+fn render(e: &Expr, style: Style, sym: &Symbols) -> (String, u8) {
+    // Wrap a child that binds more loosely than its position allows
+    let child = |c: &Expr, min: u8| {
+        let (s, p) = render(c, style, sym);
+        if p < min { format!("({})", s) } else { s }
+    };
+    match e {
+        Expr::Var(v) => (v.clone(), ATOM),
+        Expr::Pred(p, args) => (format!("{}({})", p, args.join(", ")), ATOM),
+        Expr::Not(a) => (format!("{}{}", sym.not, child(a, PREFIX)), PREFIX),
+        Expr::Necessarily(a) => (format!("{}{}", sym.necessarily, child(a, PREFIX)), PREFIX),
+        Expr::Possibly(a) => (format!("{}{}", sym.possibly, child(a, PREFIX)), PREFIX),
+        // A quantifier scopes over a prefix-level body only, as in the parser
+        Expr::Forall(v, a) => (format!("{}{}", quantifier(style, true, v), child(a, PREFIX)), PREFIX),
+        Expr::Exists(v, a) => (format!("{}{}", quantifier(style, false, v), child(a, PREFIX)), PREFIX),
+        // Left-associative chains need parentheses only around a nested right operand
+        Expr::And(a, b) => (format!("{}{}{}", child(a, AND), sym.and, child(b, PREFIX)), AND),
+        Expr::Or(a, b) => (format!("{}{}{}", child(a, OR), sym.or, child(b, AND)), OR),
+        // Implication associates to the right, so a nested left operand needs them
+        Expr::Implies(a, b) => (format!("{}{}{}", child(a, OR), sym.implies, child(b, IMPLIES)), IMPLIES),
+    }
+}
+
+/// Print with only the parentheses that precedence and associativity require
+pub fn pretty(e: &Expr, style: Style) -> String {
+    render(e, style, &symbols(style)).0
+}
+
+/// Print each input line in every style and confirm that each one parses back
+pub fn print_styles(e: &Expr) {
+    for (name, style) in [("ASCII", Style::Ascii), ("Unicode", Style::Unicode), ("LaTeX", Style::Latex)] {
+        let text = pretty(e, style);
+        let check = match Parser::new(&text).parse_all() {
+            Ok(back) if back == *e => "round-trips",
+            Ok(_) => "PARSES TO A DIFFERENT EXPRESSION",
+            Err(_) => "DOES NOT PARSE",
+        };
+        println!("{:<8} {}  [{}]", name, text, check);
+    }
+}
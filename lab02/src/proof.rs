@@ -0,0 +1,150 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+
+use crate::{collect_deps, Fact, Source};
+
+// ============================================================================
+// PROOF SEARCH AND MINIMIZATION
+// ============================================================================
+
+/// A way of deriving a fact: rule name and parent fact IDs
+type Derivation = (String, Vec<usize>);
+
+/// Node budget for the shortest-proof search
+const MAX_EXPANSIONS: usize = 200_000;
+
+/// Number of derived (non-premise) steps in the proof of `target`
+pub fn derived_steps(facts: &[Fact], target: usize) -> usize {
+    let mut steps = Vec::new();
+    collect_deps(facts, target, &mut steps);
+    steps.iter().filter(|&&i| matches!(facts[i].source, Source::Derived { .. })).count()
+}
+
+/// Every recorded derivation of a fact: the one it was added with, then alternatives
+fn derivations(facts: &[Fact], alternatives: &[Vec<Derivation>], id: usize) -> Vec<Derivation> {
+    let mut all = Vec::new();
+    if let Source::Derived { rule, parents } = &facts[id].source {
+        all.push((rule.clone(), parents.clone()));
+    }
+    all.extend(alternatives[id].iter().cloned());
+    all
+}
+
+/// Copy of the fact list with the chosen derivations swapped in
+fn with_choices(facts: &[Fact], chosen: &HashMap<usize, Derivation>) -> Vec<Fact> {
+    let mut out = facts.to_vec();
+    for (&id, (rule, parents)) in chosen {
+        out[id].source = Source::Derived { rule: rule.clone(), parents: parents.clone() };
+    }
+    out
+}
+
+/// Size of the proof of `target` under `chosen` derivations (others keep their
+/// recorded source), or None if the choices make the proof circular
+fn proof_size(facts: &[Fact], chosen: &HashMap<usize, Derivation>, target: usize) -> Option<usize> {
+    fn visit(facts: &[Fact], chosen: &HashMap<usize, Derivation>, id: usize,
+             done: &mut HashSet<usize>, active: &mut HashSet<usize>) -> bool {
+        if done.contains(&id) { return true; }
+        if !active.insert(id) { return false; } // Cycle
+        let parents = match chosen.get(&id) {
+            Some((_, ps)) => ps.clone(),
+            None => match &facts[id].source {
+                Source::Derived { parents, .. } => parents.clone(),
+                Source::Premise => Vec::new(),
+            },
+        };
+        for p in parents {
+            if !visit(facts, chosen, p, done, active) { return false; }
+        }
+        active.remove(&id);
+        done.insert(id);
+        true
+    }
+
+    let mut done = HashSet::new();
+    if !visit(facts, chosen, target, &mut done, &mut HashSet::new()) { return None; }
+    Some(done.iter().filter(|&&i| matches!(facts[i].source, Source::Derived { .. })).count())
+}
+
+/// Search for a proof of `target` with the fewest derived steps
+/// A* over partial proofs working backwards from the target: a state fixes a
+/// derivation for some facts and lists the facts still to be justified. The cost
+/// so far is the number of chosen steps and the heuristic is the number of open
+/// facts, which is admissible since each needs at least one step of its own.
+/// Returns None if the search budget runs out.
+pub fn shortest(facts: &[Fact], alternatives: &[Vec<Derivation>], target: usize) -> Option<Vec<Fact>> {
+    type State = (BTreeMap<usize, usize>, BTreeSet<usize>); // fact -> derivation index, open facts
+    let is_premise = |id: usize| matches!(facts[id].source, Source::Premise);
+    if is_premise(target) { return Some(facts.to_vec()); }
+
+    let mut options: HashMap<usize, Vec<Derivation>> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    let mut seen: HashSet<BTreeMap<usize, usize>> = HashSet::new();
+    let start: State = (BTreeMap::new(), BTreeSet::from([target]));
+    heap.push(Reverse((1, Reverse(0), start)));
+    let mut expansions = 0;
+
+    while let Some(Reverse((_, _, (chosen, open)))) = heap.pop() {
+        let Some(&goal) = open.iter().next() else {
+            // Complete proof: accept it unless the chosen derivations form a cycle
+            let map: HashMap<usize, Derivation> = chosen.iter()
+                .map(|(&id, &d)| (id, options[&id][d].clone()))
+                .collect();
+            if proof_size(facts, &map, target).is_some() { return Some(with_choices(facts, &map)); }
+            continue;
+        };
+
+        expansions += 1;
+        if expansions > MAX_EXPANSIONS { return None; }
+
+        let derivs = options.entry(goal).or_insert_with(|| derivations(facts, alternatives, goal)).clone();
+        for (d, (_, parents)) in derivs.iter().enumerate() {
+            if parents.contains(&goal) { continue; }
+            let mut next_chosen = chosen.clone();
+            next_chosen.insert(goal, d);
+            if !seen.insert(next_chosen.clone()) { continue; }
+
+            let mut next_open = open.clone();
+            next_open.remove(&goal);
+            for &p in parents {
+                if !is_premise(p) && !next_chosen.contains_key(&p) { next_open.insert(p); }
+            }
+            // Order by f = g + h, breaking ties towards deeper (more complete) states
+            let g = next_chosen.len();
+            let f = g + next_open.len();
+            heap.push(Reverse((f, Reverse(g), (next_chosen, next_open))));
+        }
+    }
+    None
+}
+
+/// Drop redundant steps from the proof of `target`
+/// Repeatedly switches a step to an alternative derivation whenever that makes the
+/// whole proof smaller (for example when a step can be justified directly from
+/// facts the proof already contains), until no switch helps.
+pub fn minimize(facts: &[Fact], alternatives: &[Vec<Derivation>], target: usize) -> Vec<Fact> {
+    let mut chosen: HashMap<usize, Derivation> = HashMap::new();
+    let mut best = proof_size(facts, &chosen, target).unwrap_or(usize::MAX);
+
+    loop {
+        let mut steps = Vec::new();
+        collect_deps(&with_choices(facts, &chosen), target, &mut steps);
+
+        let mut improved = false;
+        'search: for &id in &steps {
+            for alt in derivations(facts, alternatives, id) {
+                let mut candidate = chosen.clone();
+                candidate.insert(id, alt);
+                if let Some(size) = proof_size(facts, &candidate, target)
+                    && size < best {
+                    best = size;
+                    chosen = candidate;
+                    improved = true;
+                    break 'search;
+                }
+            }
+        }
+        if !improved { break; }
+    }
+    with_choices(facts, &chosen)
+}
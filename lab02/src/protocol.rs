@@ -0,0 +1,515 @@
+use std::io::{self, BufRead};
+use std::time::{Duration, Instant};
+
+use crate::export::{json_compact, json_string};
+use crate::{analysis, modal, negate, prove, resolution, sat, tableau, Engine, Expr, Limits, Options, Parser};
+
+// ============================================================================
+// JSON VALUES
+// ============================================================================
+
+/// A parsed JSON value (numbers are kept as f64, as in JavaScript)
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),  // Fields in input order
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "a boolean",
+            Json::Number(_) => "a number",
+            Json::Str(_) => "a string",
+            Json::Array(_) => "an array",
+            Json::Object(_) => "an object",
+        }
+    }
+
+    /// Serialize again (used to echo the request id)
+    fn to_json(&self) -> String {
+        match self {
+            Json::Null => "null".to_string(),
+            Json::Bool(b) => b.to_string(),
+            Json::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+            Json::Number(n) => n.to_string(),
+            Json::Str(s) => json_string(s),
+            Json::Array(items) => {
+                let items: Vec<String> = items.iter().map(Json::to_json).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Json::Object(fields) => {
+                let fields: Vec<String> = fields.iter().map(|(k, v)| format!("{}: {}", json_string(k), v.to_json())).collect();
+                format!("{{{}}}", fields.join(", "))
+            }
+        }
+    }
+}
+
+/// Deepest nesting of arrays and objects the reader accepts, so that a hostile
+/// line is an error instead of a stack overflow
+const MAX_DEPTH: usize = 256;
+
+/// Recursive-descent JSON reader; errors carry the 1-based column
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize, // Arrays and objects currently open
+}
+
+type ReadResult<T> = Result<T, (String, usize)>;
+
+impl Reader {
+    fn error<T>(&self, msg: impl Into<String>) -> ReadResult<T> {
+        Err((msg.into(), self.pos + 1))
+    }
+
+    fn skip_ws(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) { self.pos += 1; }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: char) -> ReadResult<()> {
+        match self.peek() {
+            Some(x) if x == c => { self.pos += 1; Ok(()) }
+            Some(x) => self.error(format!("Expected '{}', found '{}'", c, x)),
+            None => self.error(format!("Expected '{}', found end of input", c)),
+        }
+    }
+
+    fn value(&mut self) -> ReadResult<Json> {
+        match self.peek() {
+            Some(c @ ('{' | '[')) => {
+                if self.depth == MAX_DEPTH { return self.error(format!("Nesting deeper than {} levels", MAX_DEPTH)); }
+                self.depth += 1;
+                let v = if c == '{' { self.object() } else { self.array() };
+                self.depth -= 1;
+                v
+            }
+            Some('"') => self.string().map(Json::Str),
+            Some('t') => self.literal("true", Json::Bool(true)),
+            Some('f') => self.literal("false", Json::Bool(false)),
+            Some('n') => self.literal("null", Json::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => self.error(format!("Unexpected character: {}", c)),
+            None => self.error("Unexpected end of input"),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> ReadResult<Json> {
+        let end = self.pos + word.chars().count();
+        if self.chars.get(self.pos..end).is_some_and(|s| s.iter().copied().eq(word.chars())) {
+            self.pos = end;
+            Ok(value)
+        } else {
+            self.error(format!("Expected {}", word))
+        }
+    }
+
+    fn number(&mut self) -> ReadResult<Json> {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) { self.pos += 1; }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        match text.parse() {
+            Ok(n) => Ok(Json::Number(n)),
+            Err(_) => { self.pos = start; self.error(format!("Invalid number: {}", text)) }
+        }
+    }
+
+    fn hex4(&mut self) -> ReadResult<u32> {
+        let digits: String = self.chars.get(self.pos..self.pos + 4).unwrap_or_default().iter().collect();
+        match u32::from_str_radix(&digits, 16) {
+            Ok(n) if digits.len() == 4 => { self.pos += 4; Ok(n) }
+            _ => self.error("Expected four hex digits after \\u"),
+        }
+    }
+
+    /// String literal with the standard escapes, including UTF-16 surrogate pairs
+    fn string(&mut self) -> ReadResult<String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let Some(&c) = self.chars.get(self.pos) else { return self.error("Unterminated string") };
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let Some(&e) = self.chars.get(self.pos) else { return self.error("Unterminated string") };
+                    self.pos += 1;
+                    match e {
+                        '"' | '\\' | '/' => out.push(e),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'u' => {
+                            let mut code = self.hex4()?;
+                            if (0xD800..0xDC00).contains(&code) && self.chars.get(self.pos..self.pos + 2) == Some(&['\\', 'u']) {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) { return self.error("Invalid surrogate pair"); }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            match char::from_u32(code) {
+                                Some(ch) => out.push(ch),
+                                None => return self.error(format!("Invalid code point U+{:04X}", code)),
+                            }
+                        }
+                        other => { self.pos -= 1; return self.error(format!("Invalid escape: \\{}", other)); }
+                    }
+                }
+                c if (c as u32) < 0x20 => { self.pos -= 1; return self.error("Control character in string"); }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> ReadResult<Json> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        if self.peek() == Some(']') { self.pos += 1; return Ok(Json::Array(items)); }
+        loop {
+            items.push(self.value()?);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => { self.pos += 1; return Ok(Json::Array(items)); }
+                _ => return self.error("Expected ',' or ']' in array"),
+            }
+        }
+    }
+
+    fn object(&mut self) -> ReadResult<Json> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        if self.peek() == Some('}') { self.pos += 1; return Ok(Json::Object(fields)); }
+        loop {
+            if self.peek() != Some('"') { return self.error("Expected a field name"); }
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => { self.pos += 1; return Ok(Json::Object(fields)); }
+                _ => return self.error("Expected ',' or '}' in object"),
+            }
+        }
+    }
+}
+
+/// Parse one complete JSON document
+fn parse_json(text: &str) -> ReadResult<Json> {
+    let mut reader = Reader { chars: text.chars().collect(), pos: 0, depth: 0 };
+    let value = reader.value()?;
+    if reader.peek().is_some() { return reader.error("Unexpected trailing input"); }
+    Ok(value)
+}
+
+// ============================================================================
+// REQUESTS
+// ============================================================================
+
+/// Why a request line produced no verdict
+struct Error {
+    kind: &'static str,       // "json", "request" or "formula"
+    message: String,
+    field: Option<String>,    // Offending field, e.g. "premises[2]"
+    column: Option<usize>,    // 1-based position in the line or in the formula
+}
+
+impl Error {
+    fn request(field: &str, message: String) -> Self {
+        Error { kind: "request", message, field: Some(field.to_string()), column: None }
+    }
+
+    fn to_json(&self) -> String {
+        let mut out = format!("{{\"kind\": {}, \"message\": {}", json_string(self.kind), json_string(&self.message));
+        if let Some(field) = &self.field { out.push_str(&format!(", \"field\": {}", json_string(field))); }
+        if let Some(column) = self.column { out.push_str(&format!(", \"column\": {}", column)); }
+        out.push('}');
+        out
+    }
+}
+
+/// One argument to decide, with defaults taken from the command line
+struct Request {
+    premises: Vec<Expr>,
+    goal: Expr,
+    engine: Engine,
+    system: modal::System,
+    limits: Limits,
+}
+
+const FIELDS: [&str; 6] = ["id", "premises", "goal", "engine", "system", "limits"];
+const LIMIT_FIELDS: [&str; 3] = ["max_facts", "max_steps", "timeout_ms"];
+
+/// Parse a formula completely, reporting the column where it goes wrong
+fn formula(text: &str, field: &str) -> Result<Expr, Error> {
+    let mut parser = Parser::new(text);
+    let at = |parser: &Parser, message: String| Error {
+        kind: "formula", message, field: Some(field.to_string()), column: Some(parser.pos + 1),
+    };
+    parser.parse_all().map_err(|m| at(&parser, m))
+}
+
+fn string_field<'a>(v: &'a Json, field: &str) -> Result<Option<&'a str>, Error> {
+    match v.get(field) {
+        None => Ok(None),
+        Some(Json::Str(s)) => Ok(Some(s)),
+        Some(other) => Err(Error::request(field, format!("Expected a string, found {}", other.kind()))),
+    }
+}
+
+fn count(v: &Json, field: &str) -> Result<usize, Error> {
+    match v {
+        Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 && *n < 9e15 => Ok(*n as usize),
+        Json::Number(_) => Err(Error::request(field, format!("Expected a non-negative integer, found {}", v.to_json()))),
+        other => Err(Error::request(field, format!("Expected a non-negative integer, found {}", other.kind()))),
+    }
+}
+
+fn decode(v: &Json, opts: &Options) -> Result<Request, Error> {
+    let Json::Object(fields) = v else {
+        return Err(Error { kind: "request", message: format!("Expected an object, found {}", v.kind()), field: None, column: None });
+    };
+    if let Some((k, _)) = fields.iter().find(|(k, _)| !FIELDS.contains(&k.as_str())) {
+        return Err(Error::request(k, format!("Unknown field: {}", k)));
+    }
+
+    let premises = match v.get("premises") {
+        None => Vec::new(),
+        Some(Json::Array(items)) => items.iter().enumerate()
+            .map(|(i, item)| {
+                let field = format!("premises[{}]", i);
+                match item {
+                    Json::Str(s) => formula(s, &field),
+                    other => Err(Error::request(&field, format!("Expected a string, found {}", other.kind()))),
+                }
+            })
+            .collect::<Result<_, _>>()?,
+        Some(other) => return Err(Error::request("premises", format!("Expected an array, found {}", other.kind()))),
+    };
+    let goal = match string_field(v, "goal")? {
+        Some(s) => formula(s, "goal")?,
+        None => return Err(Error::request("goal", "Missing field: goal".to_string())),
+    };
+
+    let engine = match string_field(v, "engine")? {
+        None => opts.engine,
+        Some(name) => Engine::named(name).ok_or_else(|| Error::request("engine", format!("Unknown engine: {}", name)))?,
+    };
+    let system = match string_field(v, "system")? {
+        None => opts.system,
+        Some(name) => modal::System::named(name).ok_or_else(|| Error::request("system", format!("Unknown modal system: {}", name)))?,
+    };
+
+    let mut limits = Limits { max_facts: opts.limits.max_facts, max_steps: opts.limits.max_steps, timeout: opts.limits.timeout };
+    match v.get("limits") {
+        None => {}
+        Some(l @ Json::Object(fields)) => {
+            if let Some((k, _)) = fields.iter().find(|(k, _)| !LIMIT_FIELDS.contains(&k.as_str())) {
+                return Err(Error::request(&format!("limits.{}", k), format!("Unknown limit: {}", k)));
+            }
+            if let Some(n) = l.get("max_facts") { limits.max_facts = count(n, "limits.max_facts")?; }
+            if let Some(n) = l.get("max_steps") { limits.max_steps = count(n, "limits.max_steps")?; }
+            if let Some(n) = l.get("timeout_ms") { limits.timeout = Some(Duration::from_millis(count(n, "limits.timeout_ms")? as u64)); }
+        }
+        Some(other) => return Err(Error::request("limits", format!("Expected an object, found {}", other.kind()))),
+    }
+    Ok(Request { premises, goal, engine, system, limits })
+}
+
+// ============================================================================
+// RESPONSES
+// ============================================================================
+
+fn assignment(vals: &[(String, bool)]) -> String {
+    let vs: Vec<String> = vals.iter().map(|(n, b)| format!("{}: {}", json_string(n), b)).collect();
+    format!("{{{}}}", vs.join(", "))
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64() * 1000.0)
+}
+
+/// Decide the request and describe the result as the fields of a response:
+///   - forward: proof steps as in --proof-format json, and forward-chaining statistics
+///   - sat: an assignment making the premises true and the goal false
+///   - modal: a Kripke countermodel, or how many worlds were searched
+///   - resolution and tableau: the verdict only
+fn answer(req: &Request, opts: &Options) -> String {
+    let start = Instant::now();
+    match req.engine {
+        Engine::Modal => {
+            let (verdict, search) = modal::decide(&req.premises, &req.goal, req.system);
+            let (countermodel, searched) = match search {
+                modal::Search::Countermodel(model) => (model.to_json(), String::new()),
+                modal::Search::Valid(n) => ("null".to_string(), format!(", \"worlds_searched\": {}, \"complete\": true", n)),
+                modal::Search::Bounded(n, bound) => {
+                    let bound = if bound == usize::MAX { "null".to_string() } else { bound.to_string() };
+                    ("null".to_string(), format!(", \"worlds_searched\": {}, \"complete\": false, \"complete_bound\": {}", n, bound))
+                }
+            };
+            format!("\"engine\": \"modal\", \"verdict\": {}, \"proof\": null, \"countermodel\": {}, \"stats\": {{\"system\": {}{}, \"elapsed_ms\": {}}}",
+                json_string(verdict), countermodel, json_string(req.system.name()), searched, millis(start.elapsed()))
+        }
+        Engine::Sat => {
+            let (verdict, countermodel) = match sat::model(&[&req.premises[..], &[negate(&req.goal)]].concat()) {
+                None => ("Valid", "null".to_string()),
+                Some(vals) if sat::entails(&req.premises, &negate(&req.goal)) => ("Invalid", assignment(&vals)),
+                // quantified and modal formulas are atoms to the solver, so a model
+                // of that abstraction is no countermodel to the argument itself
+                Some(_) if req.premises.iter().chain([&req.goal]).any(analysis::has_quantifier) =>
+                    ("Insufficient Information", "null".to_string()),
+                Some(vals) => ("Insufficient Information", assignment(&vals)),
+            };
+            format!("\"engine\": \"sat\", \"verdict\": {}, \"proof\": null, \"countermodel\": {}, \"stats\": {{\"elapsed_ms\": {}}}",
+                json_string(verdict), countermodel, millis(start.elapsed()))
+        }
+        Engine::Resolution | Engine::Tableau => {
+            let (name, verdict) = match req.engine {
+                Engine::Resolution => ("resolution", resolution::verdict(&req.premises, &req.goal)),
+                _ => ("tableau", tableau::verdict(&req.premises, &req.goal)),
+            };
+            format!("\"engine\": {}, \"verdict\": {}, \"proof\": null, \"countermodel\": null, \"stats\": {{\"elapsed_ms\": {}}}",
+                json_string(name), json_string(verdict), millis(start.elapsed()))
+        }
+        Engine::Forward => {
+            let outcome = prove(req.premises.clone(), &req.goal, &req.limits, &opts.lemmas, false);
+            let proof = outcome.proof.map_or("null".to_string(), |id| json_compact(&outcome.facts, id));
+            let s = &outcome.stats;
+            let rules: Vec<String> = s.rules.iter().map(|(r, n)| format!("{}: {}", json_string(r), n)).collect();
+            format!("\"engine\": \"forward\", \"verdict\": {}, \"proof\": {}, \"countermodel\": null, \"stats\": {{\
+                \"facts\": {}, \"premises\": {}, \"steps\": {}, \"duplicates\": {}, \"rules\": {{{}}}, \"interned\": {}, \
+                \"horn\": {}, \"limit\": {}, \"elapsed_ms\": {}}}",
+                json_string(outcome.verdict), proof, outcome.facts.len(), s.premises, s.steps, s.duplicates, rules.join(", "),
+                s.interned, s.horn, s.limit.map_or("null".to_string(), json_string), millis(s.elapsed))
+        }
+    }
+}
+
+/// Response to one input line; `line` is the 1-based line number
+fn respond(text: &str, line: usize, opts: &Options) -> String {
+    let value = match parse_json(text) {
+        Ok(v) => v,
+        Err((message, column)) => {
+            let e = Error { kind: "json", message, field: None, column: Some(column) };
+            return format!("{{\"line\": {}, \"error\": {}}}", line, e.to_json());
+        }
+    };
+    let id = value.get("id").map_or(String::new(), |id| format!("\"id\": {}, ", id.to_json()));
+    match decode(&value, opts) {
+        Ok(req) => format!("{{{}\"line\": {}, {}}}", id, line, answer(&req, opts)),
+        Err(e) => format!("{{{}\"line\": {}, \"error\": {}}}", id, line, e.to_json()),
+    }
+}
+
+/// Answer each JSON request on stdin with one JSON response on stdout
+/// Request:  {"id": any, "premises": ["p > q", "p"], "goal": "q",
+///            "engine": "forward"|"sat"|"resolution"|"tableau"|"modal", "system": "k"|"t"|"s4"|"s5",
+///            "limits": {"max_facts": n, "max_steps": n, "timeout_ms": n}}
+///           Only "goal" is required; the rest default to the command-line options.
+/// Response: {"id", "line", "engine", "verdict", "proof", "countermodel", "stats"}
+///           or {"id", "line", "error": {"kind", "message", "field", "column"}}
+/// Blank lines are skipped; every other line gets exactly one response line.
+pub fn run(opts: &Options) {
+    let stdin = io::stdin();
+    for (n, text) in stdin.lock().lines().map_while(Result::ok).enumerate() {
+        if text.trim().is_empty() { continue; }
+        println!("{}", respond(&text, n + 1, opts));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_args;
+
+    fn ask(request: &str) -> Json {
+        parse_json(&respond(request, 1, &parse_args(&[]).unwrap())).unwrap()
+    }
+
+    fn verdict(response: &Json) -> &str {
+        match response.get("verdict") {
+            Some(Json::Str(s)) => s,
+            _ => panic!("no verdict in {}", response.to_json()),
+        }
+    }
+
+    #[test]
+    fn every_engine_answers_under_its_own_name() {
+        for engine in ["forward", "sat", "resolution", "tableau", "modal"] {
+            let r = ask(&format!(r#"{{"premises": ["p > q", "p"], "goal": "q", "engine": "{}"}}"#, engine));
+            assert_eq!(verdict(&r), "Valid");
+            assert!(matches!(r.get("engine"), Some(Json::Str(e)) if e == engine), "{}", r.to_json());
+        }
+    }
+
+    #[test]
+    fn sat_verdicts_follow_the_other_engines() {
+        let valid = ask(r#"{"premises": ["p > q", "p"], "goal": "q", "engine": "sat"}"#);
+        assert_eq!(verdict(&valid), "Valid");
+        assert!(matches!(valid.get("countermodel"), Some(Json::Null)));
+
+        let invalid = ask(r#"{"premises": ["p > q", "~q"], "goal": "p", "engine": "sat"}"#);
+        assert_eq!(verdict(&invalid), "Invalid");
+
+        // a contingent goal has a countermodel but its negation does not follow either
+        let open = ask(r#"{"premises": ["p"], "goal": "q", "engine": "sat"}"#);
+        assert_eq!(verdict(&open), "Insufficient Information");
+        assert!(matches!(open.get("countermodel"), Some(Json::Object(_))));
+    }
+
+    fn keys(response: &Json) -> Vec<&str> {
+        match response {
+            Json::Object(fields) => fields.iter().map(|(k, _)| k.as_str()).collect(),
+            _ => panic!("not an object: {}", response.to_json()),
+        }
+    }
+
+    #[test]
+    fn every_engine_uses_the_verdict_convention() {
+        let cases = [(r#"["p > q", "p"]"#, "q", "Valid"), (r#"["p > q", "~q"]"#, "p", "Invalid"), (r#"["p"]"#, "q", "Insufficient Information")];
+        for engine in ["forward", "sat", "resolution", "tableau", "modal"] {
+            for (premises, goal, expected) in cases {
+                let r = ask(&format!(r#"{{"premises": {}, "goal": "{}", "engine": "{}"}}"#, premises, goal, engine));
+                assert_eq!(verdict(&r), expected, "{} on {} |- {}", engine, premises, goal);
+            }
+        }
+    }
+
+    #[test]
+    fn responses_have_the_documented_shape() {
+        let ok = ask(r#"{"id": 7, "premises": ["p > q", "p"], "goal": "q"}"#);
+        assert_eq!(keys(&ok), ["id", "line", "engine", "verdict", "proof", "countermodel", "stats"]);
+        assert!(matches!(ok.get("id"), Some(Json::Number(n)) if *n == 7.0));
+        assert!(matches!(ok.get("line"), Some(Json::Number(n)) if *n == 1.0));
+        assert!(matches!(ok.get("stats"), Some(Json::Object(_))));
+
+        let bad = ask(r#"{"id": "a", "premises": ["p >"], "goal": "q"}"#);
+        assert_eq!(keys(&bad), ["id", "line", "error"]);
+        let error = bad.get("error").unwrap();
+        assert_eq!(keys(error), ["kind", "message", "field", "column"]);
+        assert!(matches!(error.get("kind"), Some(Json::Str(k)) if k == "formula"));
+
+        // without an id, or when the line is not JSON at all, the id is left out
+        let garbled = ask("{not json");
+        assert_eq!(keys(&garbled), ["line", "error"]);
+        assert!(matches!(garbled.get("error").and_then(|e| e.get("kind")), Some(Json::Str(k)) if k == "json"));
+    }
+}
@@ -0,0 +1,444 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use crate::pretty::{pretty, Style};
+use crate::{collect_deps, fol, forward_chain, negate, solve, Expr, Limits, Options, Parser, Source};
+
+// ============================================================================
+// SESSION STATE
+// ============================================================================
+
+/// A fact in the session
+#[derive(Clone)]
+struct Entry {
+    expr: Expr,
+    source: Source,  // Premise, or rule and 0-based parent indexes
+    extra: String,   // Extra rule argument (Addition, Universal Instantiation)
+}
+
+impl Entry {
+    /// The line that restores this fact, for saving: derived facts are written
+    /// with their conclusion, so loading rebuilds exactly this fact at this position
+    fn command(&self) -> String {
+        match &self.source {
+            Source::Premise => format!("add {}", self.expr),
+            Source::Derived { rule, parents } => {
+                let ps: Vec<String> = parents.iter().map(|p| (p + 1).to_string()).collect();
+                let step = format!("{} {} {}", rule, ps.join(" "), self.extra);
+                format!("derive {} => {}", step.trim_end(), self.expr)
+            }
+        }
+    }
+}
+
+/// Everything undo restores: the facts and the goal
+#[derive(Clone, Default)]
+struct Session {
+    facts: Vec<Entry>,
+    goal: Option<Expr>,
+}
+
+impl Session {
+    fn find(&self, e: &Expr) -> Option<usize> {
+        self.facts.iter().position(|f| f.expr == *e)
+    }
+
+    fn premises(&self) -> Vec<Expr> {
+        self.facts.iter()
+            .filter(|f| matches!(f.source, Source::Premise))
+            .map(|f| f.expr.clone())
+            .collect()
+    }
+
+    /// Remove a fact together with every fact derived from it, renumbering parents
+    /// Returns how many facts were removed.
+    fn remove(&mut self, id: usize) -> usize {
+        let mut gone = vec![false; self.facts.len()];
+        gone[id] = true;
+        // Parents always come before their children, so one pass finds every dependent
+        for i in id + 1..self.facts.len() {
+            if let Source::Derived { parents, .. } = &self.facts[i].source
+                && parents.iter().any(|&p| gone[p]) {
+                gone[i] = true;
+            }
+        }
+        let mut new_index = Vec::with_capacity(gone.len());
+        let mut next = 0;
+        for &g in &gone {
+            new_index.push(next);
+            if !g { next += 1; }
+        }
+
+        let old = std::mem::take(&mut self.facts);
+        for (i, mut f) in old.into_iter().enumerate() {
+            if gone[i] { continue; }
+            if let Source::Derived { parents, .. } = &mut f.source {
+                for p in parents.iter_mut() { *p = new_index[*p]; }
+            }
+            self.facts.push(f);
+        }
+        gone.iter().filter(|&&g| g).count()
+    }
+}
+
+// ============================================================================
+// HAND-APPLIED RULES
+// ============================================================================
+
+/// Rules that can be applied by hand: short name, full name, premises used
+/// Addition takes an extra expression and Universal Instantiation a name.
+const RULES: &[(&str, &str, &str)] = &[
+    ("mp", "Modus Ponens", "(A > B), A => B"),
+    ("mt", "Modus Tollens", "(A > B), ~B => ~A"),
+    ("hs", "Hypothetical Syllogism", "(A > B), (B > C) => (A > C)"),
+    ("ds", "Disjunctive Syllogism", "(A + B), ~A => B"),
+    ("simp", "Simplification", "(A * B) => A, B"),
+    ("conj", "Conjunction", "A, B => (A * B)"),
+    ("add", "Addition", "A <expr B> => (A + B)"),
+    ("ui", "Universal Instantiation", "forall x. A <name t> => A[x:=t]"),
+];
+
+/// Look a rule up by short name or full name (case and spaces ignored)
+fn rule_named(name: &str) -> Option<&'static str> {
+    let key: String = name.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    RULES.iter()
+        .find(|(short, full, _)| *short == key || full.replace(' ', "").to_lowercase() == key)
+        .map(|&(_, full, _)| full)
+}
+
+/// Number of facts the rule takes
+fn arity(rule: &str) -> usize {
+    match rule { "Simplification" | "Addition" | "Universal Instantiation" => 1, _ => 2 }
+}
+
+/// True for the rules that take an extra argument after their facts
+fn takes_arg(rule: &str) -> bool {
+    matches!(rule, "Addition" | "Universal Instantiation")
+}
+
+/// Apply `rule` to the given facts (in either order for two-premise rules)
+/// Returns the conclusions, or why the rule does not fit.
+fn conclude(rule: &str, facts: &[&Expr], extra: &str) -> Result<Vec<Expr>, String> {
+    let arity = arity(rule);
+    if facts.len() != arity {
+        return Err(format!("{} takes {} fact{}", rule, arity, if arity == 1 { "" } else { "s" }));
+    }
+    let pairs = if arity == 2 { vec![(facts[0], facts[1]), (facts[1], facts[0])] } else { Vec::new() };
+    let first = |f: &dyn Fn(&Expr, &Expr) -> Option<Expr>| pairs.iter().find_map(|&(x, y)| f(x, y));
+
+    let result = match rule {
+        "Modus Ponens" => first(&|x, y| match x {
+            Expr::Implies(a, b) if **a == *y => Some(*b.clone()),
+            _ => None,
+        }).map(|e| vec![e]),
+        "Modus Tollens" => first(&|x, y| match x {
+            Expr::Implies(a, b) if negate(b) == *y => Some(negate(a)),
+            _ => None,
+        }).map(|e| vec![e]),
+        "Hypothetical Syllogism" => first(&|x, y| match (x, y) {
+            (Expr::Implies(a, b), Expr::Implies(b2, c)) if b == b2 => Some(Expr::Implies(a.clone(), c.clone())),
+            _ => None,
+        }).map(|e| vec![e]),
+        "Disjunctive Syllogism" => first(&|x, y| match x {
+            Expr::Or(a, b) if negate(a) == *y => Some(*b.clone()),
+            Expr::Or(a, b) if negate(b) == *y => Some(*a.clone()),
+            _ => None,
+        }).map(|e| vec![e]),
+        "Conjunction" => Some(vec![Expr::And(Box::new(facts[0].clone()), Box::new(facts[1].clone()))]),
+        "Simplification" => match facts[0] {
+            Expr::And(a, b) => Some(vec![*a.clone(), *b.clone()]),
+            _ => None,
+        },
+        "Addition" => {
+            let other = Parser::new(extra).parse_all().map_err(|e| format!("Addition needs an expression to add: {}", e))?;
+            Some(vec![Expr::Or(Box::new(facts[0].clone()), Box::new(other))])
+        }
+        "Universal Instantiation" => {
+            let t = extra.trim();
+            if t.is_empty() || !t.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err("Universal Instantiation needs a name to instantiate with".to_string());
+            }
+            match facts[0] {
+                Expr::Forall(x, a) if fol::binds(a, t) => {
+                    return Err(format!("Cannot instantiate with {}: a quantifier inside binds {} and would capture it", t, t));
+                }
+                Expr::Forall(x, a) => Some(vec![fol::subst(a, x, t)]),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    result.ok_or_else(|| format!("{} does not apply to these facts", rule))
+}
+
+// ============================================================================
+// COMMANDS
+// ============================================================================
+
+const HELP: &str = "\
+Commands:
+  add <expr>                    add a premise
+  remove <id>                   remove a fact and everything derived from it
+  goal <expr>                   set the goal
+  list                          list the facts and the goal
+  apply <rule> <id>... [arg]    apply an inference rule to facts (see `rules`)
+  rules                         list the rules that can be applied by hand
+  hint                          suggest the next step towards the goal
+  prove                         run the automatic prover on the premises
+  undo                          undo the last change
+  save <file> / load <file>     save or load the session: its facts, derivations and goal
+  help, quit";
+
+/// Interactive prover: the REPL state plus the undo history
+struct Repl<'a> {
+    session: Session,
+    history: Vec<Session>,
+    opts: &'a Options,  // Engine options for `prove`
+    quiet: bool,        // Suppress per-command output while loading a script
+}
+
+impl<'a> Repl<'a> {
+    fn new(opts: &'a Options) -> Self {
+        Repl { session: Session::default(), history: Vec::new(), opts, quiet: false }
+    }
+
+    fn print_fact(&self, i: usize) {
+        if self.quiet { return; }
+        let f = &self.session.facts[i];
+        match &f.source {
+            Source::Premise => println!("  {}: {} [Premise]", i + 1, pretty(&f.expr, Style::Ascii)),
+            Source::Derived { rule, parents } => {
+                let ps: Vec<String> = parents.iter().map(|p| (p + 1).to_string()).collect();
+                println!("  {}: {} [{} from {}]", i + 1, pretty(&f.expr, Style::Ascii), rule, ps.join(", "));
+            }
+        }
+    }
+
+    fn list(&self) {
+        if self.session.facts.is_empty() { println!("No facts yet."); }
+        for i in 0..self.session.facts.len() { self.print_fact(i); }
+        match &self.session.goal {
+            Some(g) => println!("Goal: {}", pretty(g, Style::Ascii)),
+            None => println!("Goal: (not set)"),
+        }
+    }
+
+    fn check_goal(&self) {
+        if self.quiet { return; }
+        if let Some(g) = &self.session.goal
+            && let Some(i) = self.session.find(g) {
+            println!("Goal {} reached at fact {}.", pretty(g, Style::Ascii), i + 1);
+        }
+    }
+
+    /// Parse a 1-based fact id
+    fn fact_id(&self, s: &str) -> Result<usize, String> {
+        match s.parse::<usize>() {
+            Ok(n) if n >= 1 && n <= self.session.facts.len() => Ok(n - 1),
+            _ => Err(format!("No fact {}", s)),
+        }
+    }
+
+    /// Read `<rule> <id>... [arg]`: the rule name may be several words, then exactly
+    /// as many ids as the rule takes facts, then the extra argument if it takes one
+    fn step(&self, args: &str) -> Result<(&'static str, Vec<usize>, String), String> {
+        let words: Vec<&str> = args.split_whitespace().collect();
+        let split = words.iter().position(|w| w.parse::<usize>().is_ok())
+            .ok_or("Usage: apply <rule> <id>... [arg]")?;
+        let rule = rule_named(&words[..split].join(" "))
+            .ok_or_else(|| format!("Unknown rule: {} (see `rules`)", words[..split].join(" ")))?;
+        let end = split + arity(rule);
+        let fits = words.len() >= end
+            && words[split..end].iter().all(|w| w.parse::<usize>().is_ok())
+            && (takes_arg(rule) || words.len() == end);
+        if !fits {
+            let short = RULES.iter().find(|&&(_, full, _)| full == rule).map_or(rule, |&(short, _, _)| short);
+            let arg = match rule { "Addition" => " <expr>", "Universal Instantiation" => " <name>", _ => "" };
+            return Err(format!("Usage: {} {}{}", short, vec!["<id>"; arity(rule)].join(" "), arg));
+        }
+        let ids = words[split..end].iter().map(|w| self.fact_id(w)).collect::<Result<Vec<_>, _>>()?;
+        Ok((rule, ids, words[end..].join(" ")))
+    }
+
+    /// `apply <rule> <id>... [arg]`: add every new conclusion of the rule
+    fn apply(&mut self, args: &str) -> Result<(), String> {
+        let (rule, ids, extra) = self.step(args)?;
+        let exprs: Vec<&Expr> = ids.iter().map(|&i| &self.session.facts[i].expr).collect();
+        let conclusions = conclude(rule, &exprs, &extra)?;
+        let before = self.session.clone();
+        let mut added = 0;
+        for e in conclusions {
+            if let Some(i) = self.session.find(&e) {
+                if !self.quiet { println!("Already known as fact {}: {}", i + 1, pretty(&e, Style::Ascii)); }
+                continue;
+            }
+            self.session.facts.push(Entry {
+                expr: e,
+                source: Source::Derived { rule: rule.to_string(), parents: ids.clone() },
+                extra: extra.clone(),
+            });
+            self.print_fact(self.session.facts.len() - 1);
+            added += 1;
+        }
+        if added > 0 {
+            self.history.push(before);
+            self.check_goal();
+        }
+        Ok(())
+    }
+
+    /// `derive <rule> <id>... [arg] => <expr>` from a saved session: add exactly
+    /// this conclusion, after checking that the rule really gives it
+    fn derive(&mut self, args: &str) -> Result<(), String> {
+        let (step, conclusion) = args.split_once(" => ").ok_or("Usage: derive <rule> <id>... [arg] => <expr>")?;
+        let (rule, ids, extra) = self.step(step)?;
+        let e = Parser::new(conclusion).parse_all().map_err(|e| format!("Parse error: {}", e))?;
+        let exprs: Vec<&Expr> = ids.iter().map(|&i| &self.session.facts[i].expr).collect();
+        if !conclude(rule, &exprs, &extra)?.contains(&e) {
+            return Err(format!("{} does not give {} from these facts", rule, pretty(&e, Style::Ascii)));
+        }
+        self.session.facts.push(Entry { expr: e, source: Source::Derived { rule: rule.to_string(), parents: ids }, extra });
+        Ok(())
+    }
+
+    /// Ask forward chaining for a proof from the current facts and report its first step
+    fn hint(&self) {
+        let Some(goal) = &self.session.goal else {
+            println!("Set a goal first.");
+            return;
+        };
+        if let Some(i) = self.session.find(goal) {
+            println!("The goal is already fact {}.", i + 1);
+            return;
+        }
+        let exprs: Vec<Expr> = self.session.facts.iter().map(|f| f.expr.clone()).collect();
+        let outcome = forward_chain(exprs, goal, &Limits::default(), &self.opts.lemmas, false);
+        let Some(id) = outcome.proof else {
+            println!("No proof of {} found from the current facts ({}).", pretty(goal, Style::Ascii), outcome.verdict);
+            return;
+        };
+        if outcome.verdict != "Valid" {
+            println!("The facts entail the negation of the goal: {}", pretty(&outcome.facts[id].expr, Style::Ascii));
+            return;
+        }
+        let mut steps = Vec::new();
+        collect_deps(&outcome.facts, id, &mut steps);
+        let derived = steps.iter().filter(|&&i| matches!(outcome.facts[i].source, Source::Derived { .. })).count();
+        // The first derived step only uses session facts, which came in as premises
+        let Some(&next) = steps.iter().find(|&&i| matches!(outcome.facts[i].source, Source::Derived { .. })) else { return; };
+        let Source::Derived { rule, parents } = &outcome.facts[next].source else { return; };
+        let ps: Vec<String> = parents.iter()
+            .filter_map(|&p| self.session.find(&outcome.facts[p].expr))
+            .map(|i| (i + 1).to_string())
+            .collect();
+        println!("Hint: apply {} to {} to get {} ({} derived step{} to the goal)",
+            rule, ps.join(", "), pretty(&outcome.facts[next].expr, Style::Ascii), derived, if derived == 1 { "" } else { "s" });
+        if rule_named(rule).is_none() {
+            println!("  ({} cannot be applied by hand in this session)", rule);
+        }
+    }
+
+    /// Session as a script that `load` replays, one line per fact in order
+    fn script(&self) -> String {
+        let mut out = String::new();
+        for f in &self.session.facts {
+            out.push_str(&f.command());
+            out.push('\n');
+        }
+        if let Some(g) = &self.session.goal { out.push_str(&format!("goal {}\n", g)); }
+        out
+    }
+
+    /// Replay a saved script into a fresh session; the whole load is one undo step
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        let mut fresh = Repl { quiet: true, ..Repl::new(self.opts) };
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+            let (cmd, args) = line.split_once(' ').unwrap_or((line, ""));
+            // `apply` lines come from scripts written by hand or by older versions
+            let result = match cmd {
+                "derive" => fresh.derive(args.trim()),
+                "add" | "apply" | "goal" => fresh.command(cmd, args.trim()).map(|_| ()),
+                _ => Err(format!("unexpected command: {}", cmd)),
+            };
+            result.map_err(|e| format!("{}:{}: {}", path, n + 1, e))?;
+        }
+        self.history.push(std::mem::replace(&mut self.session, fresh.session));
+        println!("Loaded {}.", path);
+        self.list();
+        Ok(())
+    }
+
+    /// Run one command; returns Ok(false) on quit
+    fn command(&mut self, cmd: &str, args: &str) -> Result<bool, String> {
+        match cmd {
+            "add" => {
+                let e = Parser::new(args).parse_all().map_err(|e| format!("Parse error: {}", e))?;
+                if let Some(i) = self.session.find(&e) { return Err(format!("Already known as fact {}", i + 1)); }
+                self.history.push(self.session.clone());
+                self.session.facts.push(Entry { expr: e, source: Source::Premise, extra: String::new() });
+                self.print_fact(self.session.facts.len() - 1);
+                self.check_goal();
+            }
+            "remove" => {
+                let id = self.fact_id(args)?;
+                self.history.push(self.session.clone());
+                let n = self.session.remove(id);
+                println!("Removed {} fact{}.", n, if n == 1 { "" } else { "s" });
+            }
+            "goal" => {
+                let e = Parser::new(args).parse_all().map_err(|e| format!("Parse error: {}", e))?;
+                self.history.push(self.session.clone());
+                if !self.quiet { println!("Goal: {}", pretty(&e, Style::Ascii)); }
+                self.session.goal = Some(e);
+                self.check_goal();
+            }
+            "apply" => self.apply(args)?,
+            "list" => self.list(),
+            "rules" => {
+                for (short, full, shape) in RULES { println!("  {:<5} {:<24} {}", short, full, shape); }
+            }
+            "hint" => self.hint(),
+            "prove" => {
+                let goal = self.session.goal.clone().ok_or("Set a goal first.")?;
+                solve(self.session.premises(), goal, self.opts);
+            }
+            "undo" => {
+                self.session = self.history.pop().ok_or("Nothing to undo.")?;
+                println!("Undone.");
+            }
+            "save" => {
+                fs::write(args, self.script()).map_err(|e| format!("Could not write {}: {}", args, e))?;
+                println!("Saved session to {}.", args);
+            }
+            "load" => self.load(args)?,
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => return Ok(false),
+            other => return Err(format!("Unknown command: {} (type `help`)", other)),
+        }
+        Ok(true)
+    }
+}
+
+/// Read commands from stdin until `quit` or end of input
+pub fn run(opts: &Options) {
+    println!("Interactive prover. Type `help` for commands.");
+    let mut repl = Repl::new(opts);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else { println!(); break; };
+        let line = line.trim();
+        if line.is_empty() { continue; }
+        let (cmd, args) = line.split_once(' ').unwrap_or((line, ""));
+        match repl.command(cmd, args.trim()) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
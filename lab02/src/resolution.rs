@@ -0,0 +1,263 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::normal::{self, Form};
+use crate::pretty::{pretty, Style};
+use crate::{negate, Expr};
+
+// ============================================================================
+// CLAUSES
+// ============================================================================
+
+/// A literal: atom name and polarity (true = positive)
+type Literal = (String, bool);
+
+/// A clause as a set of literals (kept sorted so equal clauses compare equal)
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Clause(BTreeSet<Literal>);
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lits: Vec<String> = self.0.iter()
+            .map(|(n, pos)| if *pos { n.clone() } else { format!("~{}", n) })
+            .collect();
+        write!(f, "{{{}}}", lits.join(", "))
+    }
+}
+
+impl Clause {
+    /// True if every literal of `self` also occurs in `other`
+    fn subsumes(&self, other: &Clause) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    /// True if the clause contains both p and ~p
+    fn is_tautology(&self) -> bool {
+        self.0.iter().any(|(n, pos)| *pos && self.0.contains(&(n.clone(), false)))
+    }
+}
+
+/// How a clause entered the search
+#[derive(Clone, Debug)]
+enum Origin {
+    Premise(usize),              // Clause of the n-th premise (1-based)
+    Negated,                     // Clause of the negated goal
+    Resolvent {
+        on: String,              // Atom resolved upon
+        parents: (usize, usize), // Indices of the two parent clauses
+    },
+}
+
+struct Entry {
+    clause: Clause,
+    origin: Origin,
+}
+
+/// Split an expression into clauses via its CNF
+fn clauses_of(e: &Expr) -> Vec<Clause> {
+    let steps = normal::convert(e, Form::Cnf);
+    let cnf = steps.last().map_or(e, |s| &s.expr);
+
+    let mut conjuncts = Vec::new();
+    flatten_and(cnf, &mut conjuncts);
+    conjuncts.into_iter()
+        .map(|c| {
+            let mut lits = BTreeSet::new();
+            flatten_or(c, &mut lits);
+            Clause(lits)
+        })
+        .filter(|c| !c.is_tautology())
+        .collect()
+}
+
+fn flatten_and<'a>(e: &'a Expr, out: &mut Vec<&'a Expr>) {
+    match e {
+        Expr::And(a, b) => { flatten_and(a, out); flatten_and(b, out); }
+        _ => out.push(e),
+    }
+}
+
+fn flatten_or(e: &Expr, out: &mut BTreeSet<Literal>) {
+    match e {
+        Expr::Or(a, b) => { flatten_or(a, out); flatten_or(b, out); }
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+            out.insert((e.to_string(), true));
+        }
+        Expr::Not(inner) => match inner.as_ref() {
+            Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+                out.insert((inner.to_string(), false));
+            }
+            _ => unreachable!("CNF literal is not an atom: {}", e),
+        },
+        _ => unreachable!("CNF clause contains a non-literal: {}", e),
+    }
+}
+
+/// All resolvents of two clauses, paired with the atom resolved upon
+fn resolvents(c1: &Clause, c2: &Clause) -> Vec<(Clause, String)> {
+    let mut out = Vec::new();
+    for (name, pos) in &c1.0 {
+        let comp = (name.clone(), !pos);
+        if c2.0.contains(&comp) {
+            let mut lits: BTreeSet<Literal> = c1.0.clone();
+            lits.remove(&(name.clone(), *pos));
+            for l in &c2.0 {
+                if *l != comp { lits.insert(l.clone()); }
+            }
+            out.push((Clause(lits), name.clone()));
+        }
+    }
+    out
+}
+
+// ============================================================================
+// RESOLUTION REFUTATION
+// ============================================================================
+
+/// Clause budget for a single refutation attempt
+const MAX_CLAUSES: usize = 5000;
+
+/// Add a clause unless an active clause subsumes it; retire the clauses it subsumes
+fn add(clause: Clause, origin: Origin, entries: &mut Vec<Entry>, active: &mut Vec<bool>) -> Option<usize> {
+    for (i, e) in entries.iter().enumerate() {
+        if active[i] && e.clause.subsumes(&clause) { return None; }
+    }
+    for (i, e) in entries.iter().enumerate() {
+        if active[i] && clause.subsumes(&e.clause) { active[i] = false; }
+    }
+    entries.push(Entry { clause, origin });
+    active.push(true);
+    Some(entries.len() - 1)
+}
+
+/// Try to refute premises * ~goal using the set-of-support strategy
+/// The usable set holds the premise clauses; the set of support starts with the
+/// clauses of the negated goal, and every resolution uses at least one clause from
+/// it. Forward subsumption discards new clauses that an existing clause subsumes,
+/// and backward subsumption retires existing clauses that a new clause subsumes.
+/// Returns the clause list and the index of the empty clause on success.
+fn refute(premises: &[Expr], goal: &Expr) -> (Vec<Entry>, Option<usize>) {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut active: Vec<bool> = Vec::new();  // Not retired by subsumption
+    let mut processed: Vec<usize> = Vec::new(); // Usable clauses plus processed support
+    let mut support: Vec<usize> = Vec::new();
+
+    for (n, p) in premises.iter().enumerate() {
+        for c in clauses_of(p) {
+            if let Some(i) = add(c, Origin::Premise(n + 1), &mut entries, &mut active) { processed.push(i); }
+        }
+    }
+    for c in clauses_of(&negate(goal)) {
+        if let Some(i) = add(c, Origin::Negated, &mut entries, &mut active) { support.push(i); }
+    }
+
+    // An empty clause among the inputs is already a refutation
+    if let Some(i) = (0..entries.len()).find(|&i| active[i] && entries[i].clause.0.is_empty()) {
+        return (entries, Some(i));
+    }
+
+    while entries.len() < MAX_CLAUSES {
+        // Given clause: the shortest active clause in the set of support
+        support.retain(|&i| active[i]);
+        let Some(pos) = (0..support.len()).min_by_key(|&k| entries[support[k]].clause.0.len()) else { break; };
+        let given = support.remove(pos);
+
+        processed.retain(|&i| active[i]);
+        let partners = processed.clone();
+        processed.push(given);
+
+        for other in partners.into_iter().chain(std::iter::once(given)) {
+            if !active[given] { break; }
+            if !active[other] { continue; }
+            for (r, on) in resolvents(&entries[given].clause, &entries[other].clause) {
+                if r.is_tautology() { continue; }
+                let empty = r.0.is_empty();
+                let origin = Origin::Resolvent { on, parents: (given, other) };
+                if let Some(i) = add(r, origin, &mut entries, &mut active) {
+                    if empty { return (entries, Some(i)); }
+                    support.push(i);
+                }
+            }
+        }
+    }
+    (entries, None)
+}
+
+/// Print the refutation tree rooted at the empty clause
+fn print_tree(entries: &[Entry], idx: usize, prefix: &str, last: bool, root: bool) {
+    let e = &entries[idx];
+    let label = match &e.origin {
+        Origin::Premise(n) => format!("Premise {}", n),
+        Origin::Negated => "Negated goal".to_string(),
+        Origin::Resolvent { on, .. } => format!("Resolution on {}", on),
+    };
+    let clause = if e.clause.0.is_empty() { "{} (empty clause)".to_string() } else { e.clause.to_string() };
+
+    let (branch, child_prefix) = if root {
+        (String::new(), String::new())
+    } else if last {
+        ("`-- ".to_string(), format!("{}    ", prefix))
+    } else {
+        ("|-- ".to_string(), format!("{}|   ", prefix))
+    };
+    println!("{}{}{} [{}]", prefix, branch, clause, label);
+
+    if let Origin::Resolvent { parents: (a, b), .. } = e.origin {
+        print_tree(entries, a, &child_prefix, false, false);
+        print_tree(entries, b, &child_prefix, true, false);
+    }
+}
+
+/// The verdict of `solve` without printing the refutation
+pub fn verdict(premises: &[Expr], target: &Expr) -> &'static str {
+    if refute(premises, target).1.is_some() { "Valid" }
+    else if refute(premises, &negate(target)).1.is_some() { "Invalid" }
+    else { "Insufficient Information" }
+}
+
+/// Decide the argument by resolution refutation
+///   - premises * ~target refuted => Valid
+///   - premises * target refuted  => Invalid (the negation follows)
+///   - otherwise                  => Insufficient Information
+pub fn solve(premises: &[Expr], target: &Expr) {
+    let (entries, empty) = refute(premises, target);
+    if let Some(i) = empty {
+        println!("Determination: Valid");
+        println!("\nResolution Refutation (goal: {}):", pretty(target, Style::Ascii));
+        print_tree(&entries, i, "", true, true);
+        return;
+    }
+
+    let (neg_entries, neg_empty) = refute(premises, &negate(target));
+    if let Some(i) = neg_empty {
+        println!("Determination: Invalid");
+        println!("\nResolution Refutation (goal: {}):", pretty(&negate(target), Style::Ascii));
+        print_tree(&neg_entries, i, "", true, true);
+        return;
+    }
+
+    println!("Determination: Insufficient Information");
+    if entries.len() >= MAX_CLAUSES || neg_entries.len() >= MAX_CLAUSES {
+        println!("(clause limit of {} reached)", MAX_CLAUSES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    fn check(premises: &[&str], goal: &str) -> &'static str {
+        let premises: Vec<Expr> = premises.iter().map(|p| Parser::new(p).parse_all().unwrap()).collect();
+        let goal = Parser::new(goal).parse_all().unwrap();
+        verdict(&premises, &goal)
+    }
+
+    #[test]
+    fn verdicts_follow_the_convention() {
+        assert_eq!(check(&["p > q", "p"], "q"), "Valid");
+        // Invalid means the negation of the goal follows, not just that the goal does not
+        assert_eq!(check(&["p > q", "~q"], "p"), "Invalid");
+        assert_eq!(check(&["p"], "q"), "Insufficient Information");
+    }
+}
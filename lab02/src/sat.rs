@@ -0,0 +1,385 @@
+use crate::cnf::{self, Cnf, Lit};
+use crate::{analysis, Expr};
+
+// ============================================================================
+// DPLL SOLVER
+// ============================================================================
+
+/// Outcome of a satisfiability check
+pub enum SatResult {
+    Sat(Vec<bool>), // Model indexed by variable (index 0 unused)
+    Unsat,
+}
+
+/// Counters reported after a solver run
+#[derive(Default, Debug)]
+pub struct SatStats {
+    pub decisions: usize,
+    pub pure_literals: usize,
+    pub propagations: usize,
+    pub conflicts: usize,
+    pub learned: usize,
+}
+
+/// DPLL search with unit propagation (two watched literals), pure-literal
+/// elimination and conflict-driven backjumping (first-UIP clause learning)
+/// Literals are stored internally as codes: 2*(v-1) for v, 2*(v-1)+1 for ~v.
+pub struct Solver {
+    num_vars: usize,
+    clauses: Vec<Vec<usize>>,     // Original clauses first, then learned ones
+    original: usize,              // Number of original clauses
+    watches: Vec<Vec<usize>>,     // Literal code -> clauses watching it
+    value: Vec<Option<bool>>,     // Variable -> assignment
+    level: Vec<usize>,            // Variable -> decision level it was assigned at
+    reason: Vec<Option<usize>>,   // Variable -> clause that implied it
+    activity: Vec<f64>,           // Variable -> conflict involvement score
+    trail: Vec<usize>,            // Assigned literals in order
+    trail_lim: Vec<usize>,        // Trail length at the start of each decision level
+    qhead: usize,                 // Next trail position to propagate
+    unsat: bool,                  // Set when an empty clause was found at level 0
+    pub stats: SatStats,
+}
+
+fn code(lit: Lit) -> usize {
+    let v = lit.unsigned_abs() as usize - 1;
+    if lit > 0 { 2 * v } else { 2 * v + 1 }
+}
+
+fn var(c: usize) -> usize { c / 2 }
+
+fn neg(c: usize) -> usize { c ^ 1 }
+
+impl Solver {
+    /// Build a solver loaded with the clauses of `cnf`
+    pub fn new(cnf: &Cnf) -> Self {
+        let n = cnf.num_vars;
+        let mut s = Solver {
+            num_vars: n,
+            clauses: Vec::new(),
+            original: 0,
+            watches: vec![Vec::new(); 2 * n],
+            value: vec![None; n],
+            level: vec![0; n],
+            reason: vec![None; n],
+            activity: vec![0.0; n],
+            trail: Vec::new(),
+            trail_lim: Vec::new(),
+            qhead: 0,
+            unsat: false,
+            stats: SatStats::default(),
+        };
+
+        for clause in &cnf.clauses {
+            let mut lits: Vec<usize> = clause.iter().map(|&l| code(l)).collect();
+            lits.sort();
+            lits.dedup();
+            // Tautologies (containing both v and ~v) are always satisfied
+            if lits.windows(2).any(|w| w[0] == neg(w[1])) { continue; }
+
+            match lits.len() {
+                0 => s.unsat = true,
+                1 => {
+                    match s.lit_value(lits[0]) {
+                        Some(false) => s.unsat = true,
+                        Some(true) => {}
+                        None => s.enqueue(lits[0], None),
+                    }
+                }
+                _ => { s.attach(lits); }
+            }
+        }
+        s.original = s.clauses.len();
+        s
+    }
+
+    fn lit_value(&self, c: usize) -> Option<bool> {
+        self.value[var(c)].map(|b| b == (c & 1 == 0))
+    }
+
+    fn decision_level(&self) -> usize { self.trail_lim.len() }
+
+    fn attach(&mut self, lits: Vec<usize>) -> usize {
+        let idx = self.clauses.len();
+        self.watches[lits[0]].push(idx);
+        self.watches[lits[1]].push(idx);
+        self.clauses.push(lits);
+        idx
+    }
+
+    fn enqueue(&mut self, c: usize, reason: Option<usize>) {
+        let v = var(c);
+        self.value[v] = Some(c & 1 == 0);
+        self.level[v] = self.decision_level();
+        self.reason[v] = reason;
+        self.trail.push(c);
+    }
+
+    /// Propagate all pending assignments; returns the index of a conflicting clause
+    fn propagate(&mut self) -> Option<usize> {
+        while self.qhead < self.trail.len() {
+            let p = self.trail[self.qhead];
+            self.qhead += 1;
+            let false_lit = neg(p);
+            let watching = std::mem::take(&mut self.watches[false_lit]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+
+            for (i, &ci) in watching.iter().enumerate() {
+                if conflict.is_some() { kept.extend_from_slice(&watching[i..]); break; }
+
+                // Keep the false watch in position 1
+                if self.clauses[ci][0] == false_lit { self.clauses[ci].swap(0, 1); }
+                let first = self.clauses[ci][0];
+                if self.lit_value(first) == Some(true) { kept.push(ci); continue; }
+
+                // Look for a new literal to watch
+                let len = self.clauses[ci].len();
+                let mut moved = false;
+                for k in 2..len {
+                    let l = self.clauses[ci][k];
+                    if self.lit_value(l) != Some(false) {
+                        self.clauses[ci].swap(1, k);
+                        self.watches[l].push(ci);
+                        moved = true;
+                        break;
+                    }
+                }
+                if moved { continue; }
+
+                // Clause is unit or conflicting
+                kept.push(ci);
+                match self.lit_value(first) {
+                    Some(false) => conflict = Some(ci),
+                    _ => {
+                        self.stats.propagations += 1;
+                        self.enqueue(first, Some(ci));
+                    }
+                }
+            }
+
+            self.watches[false_lit] = kept;
+            if conflict.is_some() { return conflict; }
+        }
+        None
+    }
+
+    /// Derive the first-UIP learned clause; returns it with the level to backjump to
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let mut seen = vec![false; self.num_vars];
+        let mut learnt = vec![0]; // Slot 0 is filled with the asserting literal
+        let mut counter = 0;
+        let mut p: Option<usize> = None;
+        let mut clause = conflict;
+        let mut idx = self.trail.len();
+        let current = self.decision_level();
+
+        loop {
+            // The implied literal of a reason clause sits at position 0 and is skipped
+            let start = if p.is_some() { 1 } else { 0 };
+            for k in start..self.clauses[clause].len() {
+                let q = self.clauses[clause][k];
+                let v = var(q);
+                if seen[v] || self.level[v] == 0 { continue; }
+                seen[v] = true;
+                self.activity[v] += 1.0;
+                if self.level[v] == current { counter += 1; } else { learnt.push(q); }
+            }
+
+            // Walk back along the trail to the next marked literal
+            loop {
+                idx -= 1;
+                if seen[var(self.trail[idx])] { break; }
+            }
+            let lit = self.trail[idx];
+            seen[var(lit)] = false;
+            counter -= 1;
+            p = Some(lit);
+            if counter == 0 { break; }
+            clause = self.reason[var(lit)].expect("implied literal without a reason");
+        }
+        learnt[0] = neg(p.unwrap());
+
+        // Backjump to the highest level among the remaining literals
+        let mut back = 0;
+        if learnt.len() > 1 {
+            let mut max_i = 1;
+            for i in 2..learnt.len() {
+                if self.level[var(learnt[i])] > self.level[var(learnt[max_i])] { max_i = i; }
+            }
+            learnt.swap(1, max_i);
+            back = self.level[var(learnt[1])];
+        }
+        (learnt, back)
+    }
+
+    /// Undo every assignment above the given decision level
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() <= level { return; }
+        let keep = self.trail_lim[level];
+        for &c in &self.trail[keep..] {
+            let v = var(c);
+            self.value[v] = None;
+            self.reason[v] = None;
+        }
+        self.trail.truncate(keep);
+        self.trail_lim.truncate(level);
+        self.qhead = keep;
+    }
+
+    /// Find a literal that occurs with only one polarity among unsatisfied clauses
+    fn pure_literal(&self) -> Option<usize> {
+        let mut polarity = vec![0u8; self.num_vars]; // bit 0: positive, bit 1: negative
+        for clause in &self.clauses[..self.original] {
+            if clause.iter().any(|&l| self.lit_value(l) == Some(true)) { continue; }
+            for &l in clause {
+                if self.value[var(l)].is_none() { polarity[var(l)] |= 1 << (l & 1); }
+            }
+        }
+        polarity.iter().enumerate().find_map(|(v, &bits)| match bits {
+            1 => Some(2 * v),
+            2 => Some(2 * v + 1),
+            _ => None,
+        })
+    }
+
+    /// Pick the next decision: a pure literal if any, else the most active variable
+    fn pick_branch(&mut self) -> Option<usize> {
+        if let Some(l) = self.pure_literal() {
+            self.stats.pure_literals += 1;
+            return Some(l);
+        }
+        let mut best: Option<usize> = None;
+        for v in 0..self.num_vars {
+            if self.value[v].is_none() && best.is_none_or(|b| self.activity[v] > self.activity[b]) {
+                best = Some(v);
+            }
+        }
+        best.map(|v| {
+            self.stats.decisions += 1;
+            2 * v + 1 // Try false first
+        })
+    }
+
+    /// Run the search to completion
+    pub fn solve(&mut self) -> SatResult {
+        if self.unsat { return SatResult::Unsat; }
+
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.stats.conflicts += 1;
+                if self.decision_level() == 0 { return SatResult::Unsat; }
+
+                let (learnt, back) = self.analyze(conflict);
+                self.backtrack(back);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let ci = self.attach(learnt);
+                    self.stats.learned += 1;
+                    self.enqueue(asserting, Some(ci));
+                }
+
+                // Decay: periodically rescale activities so recent conflicts dominate
+                if self.stats.conflicts.is_multiple_of(256) {
+                    for a in &mut self.activity { *a *= 0.5; }
+                }
+            } else {
+                match self.pick_branch() {
+                    None => {
+                        let mut model = vec![false; self.num_vars + 1];
+                        for v in 0..self.num_vars { model[v + 1] = self.value[v] == Some(true); }
+                        return SatResult::Sat(model);
+                    }
+                    Some(lit) => {
+                        self.trail_lim.push(self.trail.len());
+                        self.enqueue(lit, None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// FRONT ENDS
+// ============================================================================
+
+/// Solve a DIMACS problem and print the result in SAT-competition output format
+pub fn solve_dimacs(text: &str) {
+    let cnf = match cnf::read_dimacs(text) {
+        Ok(c) => c,
+        Err(e) => { println!("c Error: {}", e); return; }
+    };
+
+    let mut solver = Solver::new(&cnf);
+    let result = solver.solve();
+    println!("c {} variables, {} clauses", cnf.num_vars, cnf.clauses.len());
+    print_stats(&solver.stats);
+    match result {
+        SatResult::Unsat => println!("s UNSATISFIABLE"),
+        SatResult::Sat(model) => {
+            println!("s SATISFIABLE");
+            let lits: Vec<String> = (1..model.len())
+                .map(|v| if model[v] { v.to_string() } else { format!("-{}", v) })
+                .collect();
+            println!("v {} 0", lits.join(" "));
+        }
+    }
+}
+
+fn print_stats(stats: &SatStats) {
+    println!("c decisions: {}, pure literals: {}, propagations: {}, conflicts: {}, learned clauses: {}",
+        stats.decisions, stats.pure_literals, stats.propagations, stats.conflicts, stats.learned);
+}
+
+/// Check whether a set of expressions is jointly satisfiable
+/// Returns the model restricted to named atoms when it is.
+pub fn model(exprs: &[Expr]) -> Option<Vec<(String, bool)>> {
+    let cnf = cnf::tseitin(exprs);
+    match Solver::new(&cnf).solve() {
+        SatResult::Unsat => None,
+        SatResult::Sat(model) => {
+            let mut vals: Vec<(String, bool)> = cnf.names.iter()
+                .map(|(&v, name)| (name.clone(), model[v]))
+                .collect();
+            vals.sort();
+            Some(vals)
+        }
+    }
+}
+
+/// True if the premises entail `goal` (premises * ~goal is unsatisfiable)
+pub fn entails(premises: &[Expr], goal: &Expr) -> bool {
+    model(&[premises, &[crate::negate(goal)]].concat()).is_none()
+}
+
+/// Decide the argument with the SAT solver instead of forward chaining
+///   - premises * ~target unsatisfiable => Valid
+///   - premises * target unsatisfiable  => Invalid (the negation follows)
+///   - otherwise neither follows; print a countermodel for the target
+///
+/// Quantified and modal formulas are treated as atoms. That keeps Valid and Invalid
+/// sound, but a countermodel then only refutes the propositional abstraction.
+pub fn decide(premises: &[Expr], target: &Expr) {
+    match model(&[premises, &[crate::negate(target)]].concat()) {
+        None => println!("Determination: Valid"),
+        Some(counter) => {
+            if entails(premises, &crate::negate(target)) {
+                println!("Determination: Invalid");
+            } else {
+                println!("Determination: Insufficient Information");
+                let vals: Vec<String> = counter.iter()
+                    .map(|(n, b)| format!("{} = {}", n, if *b { "T" } else { "F" }))
+                    .collect();
+                if premises.iter().chain(std::iter::once(target)).any(analysis::has_quantifier) {
+                    println!("\nQuantified and modal formulas were treated as atoms, so this is not a countermodel;");
+                    println!("the argument may still be valid (use the forward engine for quantifiers, modal for [] and <>).");
+                    println!("Countermodel of the propositional abstraction only: {}", vals.join(", "));
+                } else {
+                    println!("\nCountermodel (premises true, deduction false): {}", vals.join(", "));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::pretty::{pretty, Style};
+use crate::Expr;
+
+// ============================================================================
+// TERMS WITH CONSTANTS
+// ============================================================================
+
+/// Expression with truth constants, which the laws below produce and remove
+/// (Expr has no constants, so a fully simplified tautology is just T)
+#[derive(Clone, PartialEq, Debug)]
+pub enum Term {
+    Const(bool),
+    Atom(Expr),  // Variables, predicates, quantified and modal formulas
+    Not(Box<Term>),
+    And(Box<Term>, Box<Term>),
+    Or(Box<Term>, Box<Term>),
+    Implies(Box<Term>, Box<Term>),
+}
+
+/// Print `t` with as few parentheses as `pretty` uses, and report the binding
+/// strength of its main connective (1 for >, up to 5 for an atom)
+fn render(t: &Term) -> (String, u8) {
+    let child = |c: &Term, min: u8| {
+        let (s, p) = render(c);
+        if p < min { format!("({})", s) } else { s }
+    };
+    match t {
+        Term::Const(b) => ((if *b { "T" } else { "F" }).to_string(), 5),
+        Term::Atom(e @ (Expr::Var(_) | Expr::Pred(..))) => (pretty(e, Style::Ascii), 5),
+        Term::Atom(e) => (pretty(e, Style::Ascii), 4),
+        Term::Not(a) => (format!("~{}", child(a, 4)), 4),
+        Term::And(a, b) => (format!("{} * {}", child(a, 3), child(b, 4)), 3),
+        Term::Or(a, b) => (format!("{} + {}", child(a, 2), child(b, 3)), 2),
+        Term::Implies(a, b) => (format!("{} > {}", child(a, 2), child(b, 1)), 1),
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", render(self).0)
+    }
+}
+
+fn from_expr(e: &Expr) -> Term {
+    match e {
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+            Term::Atom(e.clone())
+        }
+        Expr::Not(a) => Term::Not(Box::new(from_expr(a))),
+        Expr::And(a, b) => Term::And(Box::new(from_expr(a)), Box::new(from_expr(b))),
+        Expr::Or(a, b) => Term::Or(Box::new(from_expr(a)), Box::new(from_expr(b))),
+        Expr::Implies(a, b) => Term::Implies(Box::new(from_expr(a)), Box::new(from_expr(b))),
+    }
+}
+
+/// Number of atoms, constants and connectives
+pub fn size(t: &Term) -> usize {
+    match t {
+        Term::Const(_) | Term::Atom(_) => 1,
+        Term::Not(a) => 1 + size(a),
+        Term::And(a, b) | Term::Or(a, b) | Term::Implies(a, b) => 1 + size(a) + size(b),
+    }
+}
+
+fn complement(a: &Term, b: &Term) -> bool {
+    matches!(a, Term::Not(x) if **x == *b) || matches!(b, Term::Not(x) if **x == *a)
+}
+
+// ============================================================================
+// SIMPLIFICATION LAWS
+// ============================================================================
+
+/// Operands of a chain of the same connective: ((a * b) * c) -> [a, b, c]
+fn flatten(t: &Term, and: bool, out: &mut Vec<Term>) {
+    match t {
+        Term::And(a, b) if and => { flatten(a, and, out); flatten(b, and, out); }
+        Term::Or(a, b) if !and => { flatten(a, and, out); flatten(b, and, out); }
+        _ => out.push(t.clone()),
+    }
+}
+
+/// Rebuild a chain left-nested, the way the parser reads `a * b * c`
+fn rebuild(items: Vec<Term>, and: bool) -> Term {
+    let mut it = items.into_iter();
+    let first = it.next().unwrap_or(Term::Const(and));
+    it.fold(first, |acc, x| if and { Term::And(Box::new(acc), Box::new(x)) } else { Term::Or(Box::new(acc), Box::new(x)) })
+}
+
+/// Try one law on a chain of conjuncts (`and`) or disjuncts, in this order:
+///   - Domination: A * F == F, A + T == T
+///   - Identity: A * T == A, A + F == A
+///   - Idempotence: A * A == A, A + A == A
+///   - Complement: A * ~A == F, A + ~A == T
+///   - Absorption: A * (A + B) == A, A + (A * B) == A
+fn chain_law(t: &Term, and: bool) -> Option<(Term, &'static str)> {
+    let mut items = Vec::new();
+    flatten(t, and, &mut items);
+    // In a conjunction F dominates and T is the identity; the reverse for disjunction
+    let (dominant, identity) = (Term::Const(!and), Term::Const(and));
+
+    if items.contains(&dominant) { return Some((dominant, "Domination")); }
+    if let Some(i) = items.iter().position(|x| *x == identity) {
+        items.remove(i);
+        return Some((rebuild(items, and), "Identity"));
+    }
+    for i in 0..items.len() {
+        for j in 0..items.len() {
+            if i == j { continue; }
+            if j > i && items[i] == items[j] {
+                items.remove(j);
+                return Some((rebuild(items, and), "Idempotence"));
+            }
+            if complement(&items[i], &items[j]) { return Some((dominant, "Complement")); }
+            // items[j] is a chain of the other connective containing items[i]
+            let mut inner = Vec::new();
+            flatten(&items[j], !and, &mut inner);
+            if inner.len() > 1 && inner.contains(&items[i]) {
+                items.remove(j);
+                return Some((rebuild(items, and), "Absorption"));
+            }
+        }
+    }
+    None
+}
+
+/// Try a law at the root of `t`; besides the chain laws above these are
+///   - Complement: ~T == F, ~F == T
+///   - Double Negation: ~~A == A
+///   - Identity: T > A == A, A > F == ~A
+///   - Domination: F > A == T, A > T == T
+///   - Self-Implication: A > A == T
+///   - Complement: ~A > A == A, A > ~A == ~A
+fn law_at_root(t: &Term) -> Option<(Term, &'static str)> {
+    match t {
+        Term::Not(a) => match a.as_ref() {
+            Term::Const(b) => Some((Term::Const(!b), "Complement")),
+            Term::Not(x) => Some((*x.clone(), "Double Negation")),
+            _ => None,
+        },
+        Term::Implies(a, b) => match (a.as_ref(), b.as_ref()) {
+            (Term::Const(true), _) => Some((*b.clone(), "Identity")),
+            (_, Term::Const(false)) => Some((Term::Not(a.clone()), "Identity")),
+            (Term::Const(false), _) | (_, Term::Const(true)) => Some((Term::Const(true), "Domination")),
+            _ if a == b => Some((Term::Const(true), "Self-Implication")),
+            _ if complement(a, b) => Some((*b.clone(), "Complement")),
+            _ => None,
+        },
+        Term::And(..) => chain_law(t, true),
+        Term::Or(..) => chain_law(t, false),
+        Term::Const(_) | Term::Atom(_) => None,
+    }
+}
+
+/// Rewrite the first place (innermost first, left to right) where a law applies
+/// Simplifying children first keeps the chains that the root laws see small.
+fn rewrite_once(t: &Term) -> Option<(Term, &'static str)> {
+    let rebuilt = match t {
+        Term::Const(_) | Term::Atom(_) => None,
+        Term::Not(a) => rewrite_once(a).map(|(r, l)| (Term::Not(Box::new(r)), l)),
+        Term::And(a, b) | Term::Or(a, b) | Term::Implies(a, b) => {
+            let make = |x: Term, y: Term| match t {
+                Term::And(..) => Term::And(Box::new(x), Box::new(y)),
+                Term::Or(..) => Term::Or(Box::new(x), Box::new(y)),
+                _ => Term::Implies(Box::new(x), Box::new(y)),
+            };
+            rewrite_once(a).map(|(r, l)| (make(r, *b.clone()), l))
+                .or_else(|| rewrite_once(b).map(|(r, l)| (make(*a.clone(), r), l)))
+        }
+    };
+    rebuilt.or_else(|| law_at_root(t))
+}
+
+// ============================================================================
+// TRUTH-TABLE CHECK
+// ============================================================================
+
+/// Largest number of atoms the truth-table check enumerates (2^n rows)
+const MAX_TABLE_ATOMS: usize = 20;
+
+fn atoms(t: &Term, out: &mut BTreeMap<String, usize>) {
+    match t {
+        Term::Const(_) => {}
+        Term::Atom(e) => { let n = out.len(); out.entry(e.to_string()).or_insert(n); }
+        Term::Not(a) => atoms(a, out),
+        Term::And(a, b) | Term::Or(a, b) | Term::Implies(a, b) => { atoms(a, out); atoms(b, out); }
+    }
+}
+
+fn eval(t: &Term, index: &BTreeMap<String, usize>, row: u64) -> bool {
+    match t {
+        Term::Const(b) => *b,
+        Term::Atom(e) => row >> index[&e.to_string()] & 1 == 1,
+        Term::Not(a) => !eval(a, index, row),
+        Term::And(a, b) => eval(a, index, row) && eval(b, index, row),
+        Term::Or(a, b) => eval(a, index, row) || eval(b, index, row),
+        Term::Implies(a, b) => !eval(a, index, row) || eval(b, index, row),
+    }
+}
+
+/// Compare two terms on every row of their joint truth table
+/// Returns None when there are too many atoms to enumerate.
+pub fn equivalent(a: &Term, b: &Term) -> Option<bool> {
+    let mut index = BTreeMap::new();
+    atoms(a, &mut index);
+    atoms(b, &mut index);
+    if index.len() > MAX_TABLE_ATOMS { return None; }
+    Some((0..1u64 << index.len()).all(|row| eval(a, &index, row) == eval(b, &index, row)))
+}
+
+// ============================================================================
+// SIMPLIFIER
+// ============================================================================
+
+/// Outcome of simplifying one expression
+pub struct Simplified {
+    pub steps: Vec<(Term, &'static str)>,  // Whole term after each law application
+    pub result: Term,
+    pub checked: Option<bool>,              // Truth-table verdict (None if skipped)
+}
+
+/// Apply the laws until none fires, then confirm equivalence by truth table
+/// If the check ever failed the input is returned unchanged.
+pub fn simplify(e: &Expr) -> Simplified {
+    let original = from_expr(e);
+    let mut t = original.clone();
+    let mut steps = Vec::new();
+    while let Some((next, law)) = rewrite_once(&t) {
+        steps.push((next.clone(), law));
+        t = next;
+    }
+    let checked = equivalent(&original, &t);
+    if checked == Some(false) {
+        return Simplified { steps: Vec::new(), result: original, checked };
+    }
+    Simplified { steps, result: t, checked }
+}
+
+/// Print a simplification, with the rewrite trace when `trace` is set
+pub fn print_simplification(e: &Expr, trace: bool) {
+    let s = simplify(e);
+    if trace {
+        println!("Step 1: {} [Given]", pretty(e, Style::Ascii));
+        for (n, (t, law)) in s.steps.iter().enumerate() {
+            println!("Step {}: {} [{}]", n + 2, t, law);
+        }
+    }
+    let before = size(&from_expr(e));
+    match &s.result {
+        Term::Const(true) => println!("Simplified: T (tautology)"),
+        Term::Const(false) => println!("Simplified: F (contradiction)"),
+        r => println!("Simplified: {}", r),
+    }
+    println!("Size: {} -> {} ({} rewrite{})", before, size(&s.result), s.steps.len(), if s.steps.len() == 1 { "" } else { "s" });
+    match s.checked {
+        Some(true) => println!("Truth-table check: equivalent"),
+        Some(false) => println!("Truth-table check: FAILED, keeping the original"),
+        None => println!("Truth-table check: skipped (more than {} atoms)", MAX_TABLE_ATOMS),
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::pretty::{pretty, Style};
+use crate::{analysis, negate, Expr};
+
+// ============================================================================
+// TABLEAU STRUCTURE
+// ============================================================================
+
+/// A numbered formula on the tableau
+struct Line {
+    expr: Expr,
+    origin: String,  // "Premise", "Negated goal" or the rule and line it came from
+}
+
+/// How a branch ended (only meaningful for leaves)
+enum Status {
+    Closed(usize, usize),  // Line numbers of the complementary pair
+    Open,                  // Fully expanded without contradiction
+    Inner,                 // Has children
+}
+
+/// A node holds the lines added at that point; branching creates children
+struct Node {
+    lines: Vec<usize>,
+    children: Vec<Node>,
+    status: Status,
+}
+
+/// Decomposition of a formula by the tableau rules
+enum Rule {
+    Alpha(Vec<Expr>),   // All components go on the same branch
+    Beta(Expr, Expr),   // Branch into two
+    Literal,            // Nothing to expand
+}
+
+/// Classify a formula as an α (conjunctive) or β (disjunctive) formula
+fn rule_for(e: &Expr) -> Rule {
+    match e {
+        Expr::And(a, b) => Rule::Alpha(vec![*a.clone(), *b.clone()]),
+        Expr::Or(a, b) => Rule::Beta(*a.clone(), *b.clone()),
+        Expr::Implies(a, b) => Rule::Beta(negate(a), *b.clone()),
+        Expr::Not(inner) => match inner.as_ref() {
+            Expr::Not(a) => Rule::Alpha(vec![*a.clone()]),
+            Expr::Or(a, b) => Rule::Alpha(vec![negate(a), negate(b)]),
+            Expr::Implies(a, b) => Rule::Alpha(vec![*a.clone(), negate(b)]),
+            Expr::And(a, b) => Rule::Beta(negate(a), negate(b)),
+            Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+                Rule::Literal
+            }
+        },
+        // Quantified and modal formulas are not expanded by the propositional rules
+        Expr::Var(_) | Expr::Pred(..) | Expr::Forall(..) | Expr::Exists(..) | Expr::Necessarily(_) | Expr::Possibly(_) => {
+            Rule::Literal
+        }
+    }
+}
+
+/// A complete tableau for a set of root formulas
+pub struct Tableau {
+    lines: Vec<Line>,
+    root: Node,
+}
+
+impl Tableau {
+    /// Build a fully expanded tableau from the given root formulas
+    fn build(roots: Vec<(Expr, String)>) -> Self {
+        let mut t = Tableau { lines: Vec::new(), root: Node { lines: Vec::new(), children: Vec::new(), status: Status::Open } };
+        let ids: Vec<usize> = roots.into_iter().map(|(expr, origin)| t.push(expr, origin)).collect();
+        t.root = t.grow(Vec::new(), Vec::new(), ids);
+        t
+    }
+
+    fn push(&mut self, expr: Expr, origin: String) -> usize {
+        self.lines.push(Line { expr, origin });
+        self.lines.len() - 1
+    }
+
+    /// Find a line on the path complementary to one of the `fresh` lines
+    fn contradiction(&self, path: &[usize], fresh: &[usize]) -> Option<(usize, usize)> {
+        for &f in fresh {
+            let neg = negate(&self.lines[f].expr);
+            if let Some(&p) = path.iter().find(|&&p| p != f && self.lines[p].expr == neg) {
+                return Some((p.min(f), p.max(f)));
+            }
+        }
+        None
+    }
+
+    /// Expand a branch: `path` is every line above this node, `pending` the lines on
+    /// the path not yet expanded, and `fresh` the lines that start this node.
+    /// α rules are applied before β rules so branching happens as late as possible.
+    fn grow(&mut self, mut path: Vec<usize>, mut pending: Vec<usize>, fresh: Vec<usize>) -> Node {
+        let mut node = Node { lines: Vec::new(), children: Vec::new(), status: Status::Open };
+        let mut incoming = fresh;
+
+        loop {
+            path.extend(&incoming);
+            pending.extend(&incoming);
+            node.lines.extend(&incoming);
+            if let Some((a, b)) = self.contradiction(&path, &incoming) {
+                node.status = Status::Closed(a, b);
+                return node;
+            }
+
+            let alpha = pending.iter().position(|&l| matches!(rule_for(&self.lines[l].expr), Rule::Alpha(_)));
+            let beta = pending.iter().position(|&l| matches!(rule_for(&self.lines[l].expr), Rule::Beta(..)));
+
+            if let Some(k) = alpha {
+                let src = pending.remove(k);
+                let Rule::Alpha(parts) = rule_for(&self.lines[src].expr) else { unreachable!() };
+                incoming = parts.into_iter()
+                    .map(|e| self.push(e, format!("α {}", src + 1)))
+                    .collect();
+            } else if let Some(k) = beta {
+                let src = pending.remove(k);
+                let Rule::Beta(left, right) = rule_for(&self.lines[src].expr) else { unreachable!() };
+                let l = self.push(left, format!("β {}", src + 1));
+                let r = self.push(right, format!("β {}", src + 1));
+                node.children.push(self.grow(path.clone(), pending.clone(), vec![l]));
+                node.children.push(self.grow(path, pending, vec![r]));
+                node.status = Status::Inner;
+                return node;
+            } else {
+                return node;  // Fully expanded and open
+            }
+        }
+    }
+
+    /// True when every branch is closed
+    fn is_closed(&self) -> bool {
+        fn closed(n: &Node) -> bool {
+            match n.status {
+                Status::Closed(..) => true,
+                Status::Open => false,
+                Status::Inner => n.children.iter().all(closed),
+            }
+        }
+        closed(&self.root)
+    }
+
+    /// Countermodels read off the open branches: atom -> truth value
+    fn open_branches(&self) -> Vec<BTreeMap<String, bool>> {
+        let mut out = Vec::new();
+        self.collect_open(&self.root, &mut Vec::new(), &mut out);
+        out
+    }
+
+    fn collect_open(&self, n: &Node, path: &mut Vec<usize>, out: &mut Vec<BTreeMap<String, bool>>) {
+        let depth = path.len();
+        path.extend(&n.lines);
+        match n.status {
+            Status::Open => {
+                let mut model = BTreeMap::new();
+                for &l in path.iter() {
+                    match &self.lines[l].expr {
+                        Expr::Not(inner) => {
+                            if matches!(rule_for(inner), Rule::Literal) { model.insert(inner.to_string(), false); }
+                        }
+                        e => {
+                            if matches!(rule_for(e), Rule::Literal) { model.insert(e.to_string(), true); }
+                        }
+                    }
+                }
+                out.push(model);
+            }
+            Status::Inner => for c in &n.children { self.collect_open(c, path, out); },
+            Status::Closed(..) => {}
+        }
+        path.truncate(depth);
+    }
+
+    // ------------------------------------------------------------------------
+    // RENDERING
+    // ------------------------------------------------------------------------
+
+    fn line_text(&self, l: usize) -> String {
+        format!("{}. {} [{}]", l + 1, pretty(&self.lines[l].expr, Style::Ascii), self.lines[l].origin)
+    }
+
+    /// Render the tableau as an ASCII tree
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::new();
+        self.ascii_node(&self.root, "", "", &mut out);
+        out
+    }
+
+    fn ascii_node(&self, n: &Node, first: &str, rest: &str, out: &mut String) {
+        for (i, &l) in n.lines.iter().enumerate() {
+            let lead = if i == 0 { first } else { rest };
+            let _ = writeln!(out, "{}{}", lead, self.line_text(l));
+        }
+        match n.status {
+            Status::Closed(a, b) => { let _ = writeln!(out, "{}X closed ({}, {})", rest, a + 1, b + 1); }
+            Status::Open => { let _ = writeln!(out, "{}O open", rest); }
+            Status::Inner => {
+                let last = n.children.len() - 1;
+                for (i, c) in n.children.iter().enumerate() {
+                    if i == last {
+                        self.ascii_node(c, &format!("{}`-- ", rest), &format!("{}    ", rest), out);
+                    } else {
+                        self.ascii_node(c, &format!("{}|-- ", rest), &format!("{}|   ", rest), out);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Render the tableau as a Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph tableau {\n  node [shape=box, fontname=\"monospace\"];\n");
+        let mut counter = 0;
+        self.dot_node(&self.root, &mut counter, &mut out);
+        out.push_str("}\n");
+        out
+    }
+
+    fn dot_node(&self, n: &Node, counter: &mut usize, out: &mut String) -> usize {
+        let id = *counter;
+        *counter += 1;
+        let label: Vec<String> = n.lines.iter().map(|&l| self.line_text(l).replace('"', "\\\"")).collect();
+        let _ = writeln!(out, "  n{} [label=\"{}\\l\"];", id, label.join("\\l"));
+
+        match n.status {
+            Status::Closed(a, b) => {
+                let leaf = *counter;
+                *counter += 1;
+                let _ = writeln!(out, "  n{} [label=\"X ({}, {})\", shape=plaintext];", leaf, a + 1, b + 1);
+                let _ = writeln!(out, "  n{} -> n{};", id, leaf);
+            }
+            Status::Open => {
+                let leaf = *counter;
+                *counter += 1;
+                let _ = writeln!(out, "  n{} [label=\"O\", shape=circle];", leaf);
+                let _ = writeln!(out, "  n{} -> n{};", id, leaf);
+            }
+            Status::Inner => {
+                for c in &n.children {
+                    let child = self.dot_node(c, counter, out);
+                    let _ = writeln!(out, "  n{} -> n{};", id, child);
+                }
+            }
+        }
+        id
+    }
+}
+
+// ============================================================================
+// TABLEAU PROVER
+// ============================================================================
+
+fn tableau_for(premises: &[Expr], goal: &Expr) -> Tableau {
+    let mut roots: Vec<(Expr, String)> = premises.iter().map(|p| (p.clone(), "Premise".to_string())).collect();
+    roots.push((negate(goal), "Negated goal".to_string()));
+    Tableau::build(roots)
+}
+
+/// The verdict of `solve` without printing the tableau
+pub fn verdict(premises: &[Expr], target: &Expr) -> &'static str {
+    if tableau_for(premises, target).is_closed() { "Valid" }
+    else if tableau_for(premises, &negate(target)).is_closed() { "Invalid" }
+    else { "Insufficient Information" }
+}
+
+/// Decide the argument with a semantic tableau
+///   - tableau for premises * ~target closes => Valid
+///   - tableau for premises * target closes  => Invalid (the negation follows)
+///   - otherwise open branches of the first tableau are countermodels
+///
+/// Returns the tableau that was shown so the caller can export it.
+pub fn solve(premises: &[Expr], target: &Expr) -> Tableau {
+    let t = tableau_for(premises, target);
+    if t.is_closed() {
+        println!("Determination: Valid");
+        println!("\nTableau:");
+        print!("{}", t.to_ascii());
+        return t;
+    }
+
+    let neg = tableau_for(premises, &negate(target));
+    if neg.is_closed() {
+        println!("Determination: Invalid");
+        println!("\nTableau:");
+        print!("{}", neg.to_ascii());
+        return neg;
+    }
+
+    println!("Determination: Insufficient Information");
+    println!("\nTableau:");
+    print!("{}", t.to_ascii());
+    if premises.iter().chain(std::iter::once(target)).any(analysis::has_quantifier) {
+        println!("\nQuantified and modal formulas were treated as atoms, so the open branches are not countermodels;");
+        println!("the argument may still be valid (use the forward engine for quantifiers, modal for [] and <>).");
+        println!("Open branches of the propositional abstraction only:");
+    } else {
+        println!("\nCountermodels (open branches):");
+    }
+    for model in t.open_branches() {
+        let vals: Vec<String> = model.iter()
+            .map(|(n, b)| format!("{} = {}", n, if *b { "T" } else { "F" }))
+            .collect();
+        println!("  {}", vals.join(", "));
+    }
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    fn check(premises: &[&str], goal: &str) -> &'static str {
+        let premises: Vec<Expr> = premises.iter().map(|p| Parser::new(p).parse_all().unwrap()).collect();
+        let goal = Parser::new(goal).parse_all().unwrap();
+        verdict(&premises, &goal)
+    }
+
+    #[test]
+    fn verdicts_follow_the_convention() {
+        assert_eq!(check(&["p > q", "p"], "q"), "Valid");
+        // Invalid means the negation of the goal follows, not just that the goal does not
+        assert_eq!(check(&["p > q", "~q"], "p"), "Invalid");
+        assert_eq!(check(&["p"], "q"), "Insufficient Information");
+    }
+}
@@ -0,0 +1,292 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+// limbs are base 10^9 so printing in decimal is just zero-padding each limb
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// Arbitrary-precision unsigned integer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u32>, // little-endian, no leading zero limbs (zero is empty)
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn from_u64(n: u64) -> BigUint {
+        BigUint::from_u128(n as u128)
+    }
+
+    pub fn from_u128(mut n: u128) -> BigUint {
+        let mut limbs = Vec::new();
+        while n > 0 {
+            limbs.push((n % BASE as u128) as u32);
+            n /= BASE as u128;
+        }
+        BigUint { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // drop leading zero limbs so every value has exactly one representation
+    fn trim(mut self) -> BigUint {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// Multiply by a single limb (m < 10^9), the common case for factorials
+    pub fn mul_small(&self, m: u32) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0u64;
+        for &l in &self.limbs {
+            let x = l as u64 * m as u64 + carry;
+            limbs.push((x % BASE) as u32);
+            carry = x / BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        BigUint { limbs }.trim()
+    }
+
+    /// 10^k, the scale of a fixed-point number with k decimal places
+    pub fn pow10(k: usize) -> BigUint {
+        let mut limbs = vec![0; k / BASE_DIGITS];
+        limbs.push(10u32.pow((k % BASE_DIGITS) as u32));
+        BigUint { limbs }
+    }
+
+    /// Divide by a single limb (0 < d < 10^9), returning quotient and remainder
+    pub fn div_small(&self, d: u32) -> (BigUint, u32) {
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut rem = 0u64;
+        for (i, &l) in self.limbs.iter().enumerate().rev() {
+            let x = rem * BASE + l as u64;
+            limbs[i] = (x / d as u64) as u32;
+            rem = x % d as u64;
+        }
+        (BigUint { limbs }.trim(), rem as u32)
+    }
+
+    /// Long division, returning quotient and remainder (panics on division by zero)
+    ///
+    /// Schoolbook division one base-10^9 digit at a time. The divisor is first
+    /// scaled so its top limb is at least BASE / 2, which makes the quotient digit
+    /// guessed from the top two limbs at most two too large (Knuth, TAOCP 4.3.1).
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "division by zero");
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (q, r) = self.div_small(divisor.limbs[0]);
+            return (q, BigUint::from_u64(r as u64));
+        }
+
+        let scale = (BASE / (*divisor.limbs.last().unwrap() as u64 + 1)) as u32;
+        let (a, d) = (self.mul_small(scale), divisor.mul_small(scale));
+        let top = *d.limbs.last().unwrap() as u64;
+        let n = d.limbs.len();
+
+        let mut quotient = vec![0; a.limbs.len()];
+        let mut rem = BigUint::zero();
+        for (i, &l) in a.limbs.iter().enumerate().rev() {
+            // rem = rem * BASE + next limb
+            rem.limbs.insert(0, l);
+            rem = rem.trim();
+            if rem < d {
+                continue;
+            }
+            let high = *rem.limbs.get(n).unwrap_or(&0) as u64 * BASE + rem.limbs[n - 1] as u64;
+            let mut q = (high / top).min(BASE - 1) as u32;
+            let mut product = d.mul_small(q);
+            while product > rem {
+                q -= 1;
+                product = &product - &d;
+            }
+            rem = &rem - &product;
+            quotient[i] = q;
+        }
+        (BigUint { limbs: quotient }.trim(), rem.div_small(scale).0)
+    }
+
+    /// Integer square root: the largest x with x * x <= self (Newton's method)
+    pub fn isqrt(&self) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        // start above the root so the iterates decrease monotonically onto it
+        let mut x = BigUint::pow10(self.num_digits().div_ceil(2));
+        loop {
+            let y = (&x + &self.div_rem(&x).0).div_small(2).0;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Number of decimal digits (zero has one)
+    pub fn num_digits(&self) -> usize {
+        match self.limbs.last() {
+            None => 1,
+            Some(top) => (self.limbs.len() - 1) * BASE_DIGITS + top.to_string().len(),
+        }
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() { (self, other) } else { (other, self) };
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u64;
+        for (i, &l) in long.limbs.iter().enumerate() {
+            let x = l as u64 + *short.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            limbs.push((x % BASE) as u32);
+            carry = x / BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        BigUint { limbs }
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    // only defined for self >= other; unsigned numbers cannot go negative
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(*self >= *other, "subtraction would be negative");
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &l) in self.limbs.iter().enumerate() {
+            let mut x = l as i64 - *other.limbs.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if x < 0 {
+                x += BASE as i64;
+                borrow = 1;
+            }
+            limbs.push(x as u32);
+        }
+        BigUint { limbs }.trim()
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    // schoolbook multiplication; each column sum stays below 2^64 because
+    // limb products are under 10^18 and carries are folded in as we go
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let x = limbs[i + j] + a as u64 * b as u64 + carry;
+                limbs[i + j] = x % BASE;
+                carry = x / BASE;
+            }
+            limbs[i + other.limbs.len()] += carry;
+        }
+        BigUint { limbs: limbs.into_iter().map(|l| l as u32).collect() }.trim()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return write!(f, "0");
+        };
+        write!(f, "{}", top)?;
+        for l in rest.iter().rev() {
+            write!(f, "{:09}", l)?;
+        }
+        Ok(())
+    }
+}
+
+/// Signed arbitrary-precision integer, a sign on top of a BigUint
+#[derive(Clone, Debug)]
+pub struct BigInt {
+    pub negative: bool,
+    pub magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn from_i64(n: i64) -> BigInt {
+        BigInt::from_i128(n as i128)
+    }
+
+    pub fn from_i128(n: i128) -> BigInt {
+        BigInt { negative: n < 0, magnitude: BigUint::from_u128(n.unsigned_abs()) }
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt { negative: self.negative, magnitude: &self.magnitude + &other.magnitude };
+        }
+        // opposite signs: the larger magnitude decides the sign
+        if self.magnitude >= other.magnitude {
+            BigInt { negative: self.negative, magnitude: &self.magnitude - &other.magnitude }
+        } else {
+            BigInt { negative: other.negative, magnitude: &other.magnitude - &self.magnitude }
+        }
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt { negative: self.negative != other.negative, magnitude: &self.magnitude * &other.magnitude }
+    }
+}
+
+impl Mul<&BigUint> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigUint) -> BigInt {
+        BigInt { negative: self.negative, magnitude: &self.magnitude * other }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // zero has no sign, whichever way it was reached
+        if self.negative && !self.magnitude.is_zero() {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.magnitude)
+    }
+}
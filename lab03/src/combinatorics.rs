@@ -0,0 +1,215 @@
+use crate::bigint::BigUint;
+
+// x * m for any m, using the single-limb multiply when it fits
+fn times(x: &BigUint, m: u64) -> BigUint {
+    match u32::try_from(m) {
+        Ok(small) if small < 1_000_000_000 => x.mul_small(small),
+        _ => x * &BigUint::from_u64(m),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// COUNTING SELECTIONS AND ARRANGEMENTS
+// ----------------------------------------------------------------------------
+
+/// C(n, k), the number of ways to choose k of n things (k below 10^9)
+pub fn binomial(n: u64, k: u64) -> BigUint {
+    if k > n {
+        return BigUint::zero();
+    }
+    let k = k.min(n - k);
+    let mut result = BigUint::from_u64(1);
+    // after step i the result is C(n - k + i, i), so each division is exact
+    for i in 1..=k {
+        result = times(&result, n - k + i).div_small(i as u32).0;
+    }
+    result
+}
+
+/// (k1 + k2 + ...)! / (k1! k2! ...), the ways to split a set into groups of those sizes
+pub fn multinomial(parts: &[u64]) -> BigUint {
+    let mut result = BigUint::from_u64(1);
+    let mut total = 0;
+    // choose the places of each group among the ones filled so far
+    for &k in parts {
+        total += k;
+        result = &result * &binomial(total, k);
+    }
+    result
+}
+
+/// P(n, r) = n! / (n - r)!, ordered selections of r of n things without repetition
+pub fn permutations(n: u64, r: u64) -> BigUint {
+    if r > n {
+        return BigUint::zero();
+    }
+    let mut result = BigUint::from_u64(1);
+    for m in n - r + 1..=n {
+        result = times(&result, m);
+    }
+    result
+}
+
+/// n^r, ordered selections of r of n things when each may be picked again
+pub fn permutations_with_repetition(n: u64, r: u64) -> BigUint {
+    let mut result = BigUint::from_u64(1);
+    for _ in 0..r {
+        result = times(&result, n);
+    }
+    result
+}
+
+/// The nth Catalan number C(2n, n) / (n + 1): bracketings, binary trees, Dyck paths
+pub fn catalan(n: u64) -> BigUint {
+    binomial(2 * n, n).div_small((n + 1) as u32).0
+}
+
+/// !n, the permutations of n things that leave none of them in place
+pub fn derangements(n: u64) -> BigUint {
+    // !n = (n - 1)(!(n - 1) + !(n - 2)), starting from !0 = 1 and !1 = 0
+    let (mut before, mut current) = (BigUint::from_u64(1), BigUint::zero());
+    if n == 0 {
+        return before;
+    }
+    for m in 2..=n {
+        let next = times(&(&before + &current), m - 1);
+        before = current;
+        current = next;
+    }
+    current
+}
+
+// ----------------------------------------------------------------------------
+// STIRLING AND BELL NUMBERS
+// ----------------------------------------------------------------------------
+
+/// Which Stirling numbers
+#[derive(Clone, Copy)]
+pub enum Stirling {
+    First,  // unsigned: permutations of n things with k cycles
+    Second, // partitions of n things into k nonempty blocks
+}
+
+impl Stirling {
+    pub fn name(self) -> &'static str {
+        match self {
+            Stirling::First => "Stirling numbers of the first kind (unsigned)",
+            Stirling::Second => "Stirling numbers of the second kind",
+        }
+    }
+
+    /// How a value is written, e.g. [5, 2] for the first kind and {5, 2} for the second
+    pub fn notation(self, n: u64, k: u64) -> String {
+        match self {
+            Stirling::First => format!("[{}, {}]", n, k),
+            Stirling::Second => format!("{{{}, {}}}", n, k),
+        }
+    }
+
+    // row n of the triangle from row n - 1:
+    //   first kind:  [n, k] = (n - 1) [n - 1, k] + [n - 1, k - 1]
+    //   second kind: {n, k} = k {n - 1, k} + {n - 1, k - 1}
+    fn next_row(self, previous: &[BigUint], n: u64) -> Vec<BigUint> {
+        (0..=n as usize).map(|k| {
+            let factor = match self {
+                Stirling::First => n - 1,
+                Stirling::Second => k as u64,
+            };
+            let stay = previous.get(k).map_or(BigUint::zero(), |x| times(x, factor));
+            let grow = if k > 0 { previous[k - 1].clone() } else { BigUint::zero() };
+            &stay + &grow
+        }).collect()
+    }
+
+    /// Rows 0 to n of the triangle
+    pub fn triangle(self, n: u64) -> Vec<Vec<BigUint>> {
+        let mut rows = vec![vec![BigUint::from_u64(1)]];
+        for m in 1..=n {
+            let next = self.next_row(&rows[rows.len() - 1], m);
+            rows.push(next);
+        }
+        rows
+    }
+
+    /// Row n of the triangle, keeping only one row at a time
+    pub fn row(self, n: u64) -> Vec<BigUint> {
+        let mut row = vec![BigUint::from_u64(1)];
+        for m in 1..=n {
+            row = self.next_row(&row, m);
+        }
+        row
+    }
+}
+
+/// B(n), the number of ways to partition a set of n things: the sum of {n, k} over k
+pub fn bell(n: u64) -> BigUint {
+    Stirling::Second.row(n).iter().fold(BigUint::zero(), |sum, x| &sum + x)
+}
+
+// ----------------------------------------------------------------------------
+// INTEGER PARTITIONS
+// ----------------------------------------------------------------------------
+
+/// p(n), the ways to write n as a sum of positive integers ignoring order
+///
+/// Euler's pentagonal number theorem gives
+///   p(n) = p(n-1) + p(n-2) - p(n-5) - p(n-7) + p(n-12) + p(n-15) - ...
+/// over the generalized pentagonal numbers k(3k - 1)/2 and k(3k + 1)/2,
+/// with signs +, +, -, -, ... by k. The sum is kept as a positive and a
+/// negative part, since p(n) itself is never negative.
+pub fn partitions(n: u64) -> BigUint {
+    let n = n as usize;
+    let mut p = vec![BigUint::from_u64(1)];
+    for m in 1..=n {
+        let (mut plus, mut minus) = (BigUint::zero(), BigUint::zero());
+        for k in 1.. {
+            let first = k * (3 * k - 1) / 2;
+            if first > m {
+                break;
+            }
+            let sum = if k % 2 == 1 { &mut plus } else { &mut minus };
+            *sum = &*sum + &p[m - first];
+            let second = k * (3 * k + 1) / 2;
+            if second <= m {
+                *sum = &*sum + &p[m - second];
+            }
+        }
+        p.push(&plus - &minus);
+    }
+    p.swap_remove(n)
+}
+
+// ----------------------------------------------------------------------------
+// TABLES
+// ----------------------------------------------------------------------------
+
+/// Rows 0 to n of Pascal's triangle, each row built from the one above
+pub fn pascal(n: u64) -> Vec<Vec<BigUint>> {
+    let mut rows = vec![vec![BigUint::from_u64(1)]];
+    for _ in 0..n {
+        let above = &rows[rows.len() - 1];
+        let mut row = vec![BigUint::from_u64(1)];
+        row.extend(above.windows(2).map(|w| &w[0] + &w[1]));
+        row.push(BigUint::from_u64(1));
+        rows.push(row);
+    }
+    rows
+}
+
+/// Print a triangle with row n and column k, each column as wide as its largest entry
+pub fn print_triangle(title: &str, rows: &[Vec<BigUint>]) {
+    let cells: Vec<Vec<String>> = rows.iter().map(|r| r.iter().map(|x| x.to_string()).collect()).collect();
+    let label = rows.len().saturating_sub(1).to_string().len().max(1);
+    let width: Vec<usize> = (0..rows.len())
+        .map(|k| cells.iter().filter_map(|r| r.get(k)).map(String::len).max().unwrap_or(0).max(k.to_string().len()))
+        .collect();
+
+    println!("\n{}\n", title);
+    let header: Vec<String> = width.iter().enumerate().map(|(k, w)| format!("{:>w$}", k, w = w)).collect();
+    println!("{:>label$} | {}", "n\\k", header.join(" "), label = label.max(3));
+    println!("{}", "-".repeat(label.max(3) + 3 + header.join(" ").len()));
+    for (n, row) in cells.iter().enumerate() {
+        let row: Vec<String> = row.iter().enumerate().map(|(k, x)| format!("{:>w$}", x, w = width[k])).collect();
+        println!("{:>label$} | {}", n, row.join(" "), label = label.max(3));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::bigint::{BigInt, BigUint};
+
+// every constant is computed as a fixed-point integer floor(x * 10^(digits + GUARD))
+// and then rounded, so the extra digits absorb truncation in the intermediate steps
+const GUARD: usize = 10;
+
+// first 100 decimals of each constant, to check the big-number code at runtime
+const KNOWN_PI: &str = "3.1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
+const KNOWN_E: &str = "2.7182818284590452353602874713526624977572470936999595749669676277240766303535475945713821785251664274";
+const KNOWN_SQRT2: &str = "1.4142135623730950488016887242096980785696718753769480731766797379907324784621070388503875343276415727";
+
+/// Which constant to compute
+#[derive(Clone, Copy)]
+pub enum Constant {
+    Pi,
+    E,
+    Sqrt2,
+}
+
+impl Constant {
+    pub fn name(self) -> &'static str {
+        match self {
+            Constant::Pi => "Pi",
+            Constant::E => "e",
+            Constant::Sqrt2 => "Square root of 2",
+        }
+    }
+
+    fn known(self) -> &'static str {
+        match self {
+            Constant::Pi => KNOWN_PI,
+            Constant::E => KNOWN_E,
+            Constant::Sqrt2 => KNOWN_SQRT2,
+        }
+    }
+
+    /// The constant to `digits` decimal places, checked against the known prefix
+    pub fn digits(self, digits: usize) -> Digits {
+        let places = digits + GUARD;
+        let scaled = match self {
+            Constant::Pi => pi_scaled(places),
+            Constant::E => e_scaled(places),
+            Constant::Sqrt2 => (&BigUint::from_u64(2) * &BigUint::pow10(2 * places)).isqrt(),
+        };
+
+        // the unrounded digits must agree with the known ones as far as both go
+        let full = scaled.to_string();
+        let known = self.known().replace('.', "");
+        let checked = digits.min(known.len() - 1);
+        let matches = full[..checked + 1] == known[..checked + 1];
+
+        // round half up on the guard digits
+        let half = BigUint::pow10(GUARD).div_small(2).0;
+        let rounded = (&scaled + &half).div_rem(&BigUint::pow10(GUARD)).0.to_string();
+        let point = rounded.len() - digits;
+        let text = if digits == 0 { rounded } else { format!("{}.{}", &rounded[..point], &rounded[point..]) };
+        Digits { text, checked, matches }
+    }
+}
+
+/// A computed constant and the result of checking it
+pub struct Digits {
+    pub text: String,   // rounded to the requested places, as "3.14..."
+    pub checked: usize, // leading decimals compared with the known value
+    pub matches: bool,
+}
+
+// ----------------------------------------------------------------------------
+// PI (CHUDNOVSKY)
+// ----------------------------------------------------------------------------
+
+// 640320^3 / 24
+const C3_OVER_24: u64 = 10_939_058_860_032_000;
+
+// binary splitting over terms a..b of the series, returning P(a,b), Q(a,b), T(a,b)
+// so that the partial sum is T / Q; see https://en.wikipedia.org/wiki/Chudnovsky_algorithm
+fn split(a: u64, b: u64) -> (BigUint, BigUint, BigInt) {
+    if b - a == 1 {
+        let (p, q) = if a == 0 {
+            (BigUint::from_u64(1), BigUint::from_u64(1))
+        } else {
+            let p = &(&BigUint::from_u64(6 * a - 5) * &BigUint::from_u64(2 * a - 1)) * &BigUint::from_u64(6 * a - 1);
+            let q = &BigUint::from_u64(a * a * a) * &BigUint::from_u64(C3_OVER_24);
+            (p, q)
+        };
+        let t = &p * &BigUint::from_u64(13_591_409 + 545_140_134 * a);
+        return (p, q, BigInt { negative: a % 2 == 1, magnitude: t });
+    }
+    let m = (a + b) / 2;
+    let (p1, q1, t1) = split(a, m);
+    let (p2, q2, t2) = split(m, b);
+    let t = &(&t1 * &q2) + &(&t2 * &p1);
+    (&p1 * &p2, &q1 * &q2, t)
+}
+
+// floor(pi * 10^places): pi = 426880 * sqrt(10005) * Q / T
+fn pi_scaled(places: usize) -> BigUint {
+    // each term adds about 14.18 digits
+    let terms = (places as u64) / 14 + 2;
+    let (_, q, t) = split(0, terms);
+    let sqrt_c = (&BigUint::from_u64(10005) * &BigUint::pow10(2 * places)).isqrt();
+    let numerator = &sqrt_c.mul_small(426_880) * &q;
+    numerator.div_rem(&t.magnitude).0
+}
+
+// ----------------------------------------------------------------------------
+// E
+// ----------------------------------------------------------------------------
+
+// floor(e * 10^places) from e = sum of 1/k!, dividing the previous term by k
+fn e_scaled(places: usize) -> BigUint {
+    let mut term = BigUint::pow10(places);
+    let mut sum = term.clone();
+    let mut k = 1;
+    while !term.is_zero() {
+        term = term.div_small(k).0;
+        sum = &sum + &term;
+        k += 1;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_match_known_digits() {
+        for constant in [Constant::Pi, Constant::E, Constant::Sqrt2] {
+            // a few extra places so rounding cannot reach the compared digits
+            let result = constant.digits(110);
+            assert!(result.text.starts_with(constant.known()), "{} = {}", constant.name(), result.text);
+            assert_eq!(result.checked, 100);
+            assert!(result.matches);
+        }
+    }
+
+    #[test]
+    fn rounds_to_the_requested_places() {
+        assert_eq!(Constant::Pi.digits(4).text, "3.1416");
+        assert_eq!(Constant::Pi.digits(0).text, "3");
+        assert_eq!(Constant::E.digits(3).text, "2.718");
+        assert_eq!(Constant::Sqrt2.digits(5).text, "1.41421");
+    }
+}
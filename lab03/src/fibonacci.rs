@@ -0,0 +1,316 @@
+use std::time::{Duration, Instant};
+
+use crate::bigint::{BigInt, BigUint};
+
+// ----------------------------------------------------------------------------
+// EXACT FIBONACCI
+// ----------------------------------------------------------------------------
+
+// fibonacci sequence algorithm from https://benjaminbrandt.com/fibonacci-in-rust/
+// with big integers, since u64 overflows just past F(93); O(n) additions
+pub fn iterative(n: u64) -> BigUint {
+    let mut a = BigUint::from_u64(1);
+    let mut b = BigUint::zero();
+    let mut count = 0;
+
+    while count < n {
+        let tmp = &a + &b;
+        b = a;
+        a = tmp;
+        count += 1;
+    }
+
+    b
+}
+
+/// F(n) by fast doubling, O(log n) big-number multiplications
+pub fn fast_doubling(n: u64) -> BigUint {
+    doubling(n).0
+}
+
+/// L(n), the Lucas numbers 2, 1, 3, 4, 7, ...: L(n) = 2 F(n+1) - F(n)
+pub fn lucas(n: u64) -> BigUint {
+    let (f, g) = doubling(n);
+    &g.mul_small(2) - &f
+}
+
+// (F(n), F(n+1)) from (F(k), F(k+1)) with k = n / 2, using
+//   F(2k) = F(k) * (2 F(k+1) - F(k))
+//   F(2k+1) = F(k)^2 + F(k+1)^2
+fn doubling(n: u64) -> (BigUint, BigUint) {
+    if n == 0 {
+        return (BigUint::zero(), BigUint::from_u64(1));
+    }
+    let (a, b) = doubling(n / 2);
+    let even = &a * &(&b.mul_small(2) - &a);
+    let odd = &(&a * &a) + &(&b * &b);
+    if n.is_multiple_of(2) {
+        (even, odd)
+    } else {
+        let next = &even + &odd;
+        (odd, next)
+    }
+}
+
+/// F(n) as the corner of the matrix [[1, 1], [1, 0]]^n
+pub fn matrix(n: u64) -> BigUint {
+    Recurrence::FIBONACCI.term(n).magnitude
+}
+
+// ----------------------------------------------------------------------------
+// SECOND-ORDER RECURRENCES
+// ----------------------------------------------------------------------------
+
+/// a(n) = p * a(n-1) + q * a(n-2), starting from a(0) and a(1)
+#[derive(Clone, Copy)]
+pub struct Recurrence {
+    pub p: i64,
+    pub q: i64,
+    pub a0: i64,
+    pub a1: i64,
+}
+
+impl Recurrence {
+    pub const FIBONACCI: Recurrence = Recurrence { p: 1, q: 1, a0: 0, a1: 1 };
+    pub const LUCAS: Recurrence = Recurrence { p: 1, q: 1, a0: 2, a1: 1 };
+
+    /// a(n) exactly: [a(n+1), a(n)] = [[p, q], [1, 0]]^n [a(1), a(0)]
+    pub fn term(&self, n: u64) -> BigInt {
+        let step = [[self.p, self.q], [1, 0]].map(|row| row.map(BigInt::from_i64));
+        let m = power(step, n, [[1, 0], [0, 1]].map(|row| row.map(BigInt::from_i64)), |x, y| {
+            let cell = |i: usize, j: usize| &(&x[i][0] * &y[0][j]) + &(&x[i][1] * &y[1][j]);
+            [[cell(0, 0), cell(0, 1)], [cell(1, 0), cell(1, 1)]]
+        });
+        &(&m[1][0] * &BigInt::from_i64(self.a1)) + &(&m[1][1] * &BigInt::from_i64(self.a0))
+    }
+
+    /// a(n) mod m (m >= 1), with the result in 0..m even for negative terms
+    pub fn term_mod(&self, n: u64, m: u64) -> u64 {
+        let r = |x: i64| (x as i128).rem_euclid(m as i128) as u64;
+        let step = [[r(self.p), r(self.q)], [1 % m, 0]];
+        let mx = power(step, n, [[1 % m, 0], [0, 1 % m]], |x, y| {
+            let cell = |i: usize, j: usize| (mul_mod(x[i][0], y[0][j], m) + mul_mod(x[i][1], y[1][j], m)) % m;
+            [[cell(0, 0), cell(0, 1)], [cell(1, 0), cell(1, 1)]]
+        });
+        (mul_mod(mx[1][0], r(self.a1), m) + mul_mod(mx[1][1], r(self.a0), m)) % m
+    }
+
+    pub fn describe(&self) -> String {
+        format!("a(n) = {} a(n-1) + {} a(n-2), a(0) = {}, a(1) = {}", self.p, self.q, self.a0, self.a1)
+    }
+}
+
+type Matrix<T> = [[T; 2]; 2];
+
+// square-and-multiply over whatever the 2x2 matrices hold
+fn power<T: Clone>(mut base: Matrix<T>, mut n: u64, mut acc: Matrix<T>,
+                   mul: impl Fn(&Matrix<T>, &Matrix<T>) -> Matrix<T>) -> Matrix<T> {
+    while n > 0 {
+        if n % 2 == 1 {
+            acc = mul(&acc, &base);
+        }
+        n /= 2;
+        if n > 0 {
+            base = mul(&base, &base);
+        }
+    }
+    acc
+}
+
+// ----------------------------------------------------------------------------
+// MODULAR FIBONACCI AND THE PISANO PERIOD
+// ----------------------------------------------------------------------------
+
+// a * b mod m without overflow, for any m up to u64::MAX
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// (F(n) mod m, F(n+1) mod m) by fast doubling, for m >= 1
+pub fn fib_mod(n: u64, m: u64) -> (u64, u64) {
+    if n == 0 {
+        return (0, 1 % m);
+    }
+    let (a, b) = fib_mod(n / 2, m);
+    let even = mul_mod(a, ((2 * b as u128 + m as u128 - a as u128) % m as u128) as u64, m);
+    let odd = ((mul_mod(a, a, m) as u128 + mul_mod(b, b, m) as u128) % m as u128) as u64;
+    if n.is_multiple_of(2) {
+        (even, odd)
+    } else {
+        (odd, ((even as u128 + odd as u128) % m as u128) as u64)
+    }
+}
+
+/// The Pisano period: the length of the cycle of F(n) mod m
+///
+/// Worked out one prime power at a time and combined with lcm. For a prime p,
+/// pi(p) divides p - 1 when p = ±1 mod 5 and 2(p + 1) when p = ±2 mod 5, and
+/// pi(p^k) divides p^(k-1) pi(p), so each bound is cut down by its own prime
+/// factors for as long as it remains a period.
+pub fn pisano(m: u64) -> u64 {
+    let mut period = 1;
+    for (p, k) in factorize(m) {
+        let pk = p.pow(k);
+        let base = match p {
+            2 => 3,
+            5 => 20,
+            _ if p % 5 == 1 || p % 5 == 4 => p - 1,
+            _ => 2 * (p + 1),
+        };
+        let mut t = base * p.pow(k - 1);
+        for (q, _) in factorize(t) {
+            while t.is_multiple_of(q) && fib_mod(t / q, pk) == (0, 1) {
+                t /= q;
+            }
+        }
+        period = lcm(period, t);
+    }
+    period
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+// ----------------------------------------------------------------------------
+// FACTORING (for the Pisano period)
+// ----------------------------------------------------------------------------
+
+// deterministic Miller-Rabin: these bases are enough for every 64-bit number
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+    let (mut d, mut s) = (n - 1, 0);
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    'bases: for a in BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn pow_mod(mut a: u64, mut e: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    while e > 0 {
+        if e % 2 == 1 {
+            result = mul_mod(result, a, m);
+        }
+        a = mul_mod(a, a, m);
+        e /= 2;
+    }
+    result
+}
+
+// a nontrivial factor of an odd composite n, by Pollard's rho with Brent's cycle finding
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| (mul_mod(x, x, n) + c) % n;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        let mut power = 1;
+        let mut steps = 0;
+        while d == 1 {
+            if steps == power {
+                x = y;
+                power *= 2;
+                steps = 0;
+            }
+            y = f(y);
+            steps += 1;
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+/// Prime factors of n with their exponents, smallest prime first
+fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut primes = Vec::new();
+    let mut rest = n;
+    // small primes by trial division, then split whatever is left
+    for p in 2..1000 {
+        while rest.is_multiple_of(p) {
+            primes.push(p);
+            rest /= p;
+        }
+    }
+    let mut pending = vec![rest];
+    while let Some(x) = pending.pop() {
+        if x == 1 {
+            continue;
+        }
+        if is_prime(x) {
+            primes.push(x);
+        } else {
+            let d = pollard_rho(x);
+            pending.push(d);
+            pending.push(x / d);
+        }
+    }
+    primes.sort();
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, k)) if *q == p => *k += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+// ----------------------------------------------------------------------------
+// BENCHMARK
+// ----------------------------------------------------------------------------
+
+/// One method's timing in a benchmark
+pub struct Timing {
+    pub method: &'static str,
+    pub time: Option<Duration>, // None when skipped as too slow
+    pub agrees: bool,           // same result as fast doubling
+}
+
+// the O(n) loop takes too long past this
+const ITERATIVE_LIMIT: u64 = 200_000;
+
+/// Time every method on F(n), checking each against fast doubling
+pub fn benchmark(n: u64) -> (BigUint, Vec<Timing>) {
+    let start = Instant::now();
+    let reference = fast_doubling(n);
+    let mut timings = vec![Timing { method: "fast doubling", time: Some(start.elapsed()), agrees: true }];
+
+    let start = Instant::now();
+    let m = matrix(n);
+    timings.push(Timing { method: "matrix power", time: Some(start.elapsed()), agrees: m == reference });
+
+    if n <= ITERATIVE_LIMIT {
+        let start = Instant::now();
+        let it = iterative(n);
+        timings.push(Timing { method: "iterative loop", time: Some(start.elapsed()), agrees: it == reference });
+    } else {
+        timings.push(Timing { method: "iterative loop", time: None, agrees: true });
+    }
+    (reference, timings)
+}
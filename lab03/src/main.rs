@@ -1,53 +1,202 @@
+use std::fs::File;
 use std::io;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+mod bigint;
+mod combinatorics;
+mod constants;
+mod fibonacci;
+mod recurrence;
+mod spigot;
+
+use bigint::BigUint;
+use combinatorics::Stirling;
+use constants::Constant;
+use fibonacci::Recurrence;
+
+// how big results get printed
+#[derive(Clone, Copy)]
+enum OutputMode {
+    Full,       // every digit
+    Truncated,  // first and last digits of long results
+    DigitCount, // only how many digits there are
+}
+
+impl OutputMode {
+    fn name(self) -> &'static str {
+        match self {
+            OutputMode::Full => "full",
+            OutputMode::Truncated => "truncated",
+            OutputMode::DigitCount => "digit count only",
+        }
+    }
+}
+
+// most decimal places offered for pi, e and the square root of 2
+const MAX_DIGITS: u64 = 20_000;
+// most decimal places offered for the spigot; it needs no big numbers, but its
+// time still grows with the square of the digits
+const MAX_STREAM_DIGITS: u64 = 100_000;
+// decimals per line when streaming to the screen
+const STREAM_LINE: usize = 50;
+
+// largest n offered for a factorial (about 213,000 digits)
+const MAX_FACTORIAL_N: u64 = 50_000;
+// largest n offered for an exact Fibonacci number (about 209,000 digits)
+const MAX_FIB_N: u64 = 1_000_000;
+// exact terms of other recurrences are cut off at about as many digits
+const MAX_TERM_DIGITS: f64 = 210_000.0;
+// largest n and modulus for the modular mode
+const MAX_MOD_N: u64 = 1_000_000_000_000_000_000;
+
+// largest n for the combinatorial counts built one factor or term at a time
+const MAX_COMB_N: u64 = 20_000;
+// Stirling and Bell numbers fill a whole triangle of big numbers
+const MAX_STIRLING_N: u64 = 1_000;
+// p(n) sums over all smaller partition numbers
+const MAX_PARTITION_N: u64 = 50_000;
+// most rows printed for Pascal's and the Stirling triangles
+const MAX_TABLE_ROWS: u64 = 20;
+
+// results longer than this get shortened in truncated mode
+const TRUNCATE_OVER: usize = 60;
+// digits kept at each end of a truncated result
+const SHOWN_DIGITS: usize = 20;
 
 fn main(){
+    let mut mode = OutputMode::Truncated;
 
     loop{
         println!("\nChoose a calculation to do:
 1. Factorial of n
 2. Fibonacci sequence of n numbers
-3. Pi calculated to n digits up to 15
-4. Quit
-----------------------------------------");
+3. Pi calculated to n digits
+4. e calculated to n digits
+5. Square root of 2 calculated to n digits
+6. Pi streamed digit by digit (spigot), to the screen or a file
+7. More Fibonacci: Lucas numbers, other recurrences, mod m, Pisano period, benchmark
+8. Solve a linear recurrence: characteristic polynomial, roots and closed form
+9. Combinatorics: nCr, nPr, Catalan, Stirling, Bell, partitions, derangements, tables
+10. Big number display (currently: {})
+11. Quit
+----------------------------------------", mode.name());
 
         // call input function
         let number = get_input();
 
-        // match user input to 1 of 4 values
+        // match user input to one of the menu entries
         match number {
             1 => {
-                print!("\nType a number to find the factorial of: ");
+                print!("\nType a number to find the factorial of (up to {}): ", MAX_FACTORIAL_N);
                 // flush stdout to actually print
                 io::stdout().flush().unwrap();
-                let fac_num = get_input();
+                let mut fac_num = get_input();
+                if fac_num > MAX_FACTORIAL_N {
+                    println!("\nInput larger than {}, calculating only the factorial of {}.", MAX_FACTORIAL_N, MAX_FACTORIAL_N);
+                    fac_num = MAX_FACTORIAL_N;
+                }
 
                 // calc and print
-                println!("\n{}! = {}", fac_num, factorial(fac_num));
+                println!("\n{}! = {}", fac_num, format_big(&factorial(fac_num), mode));
             }
 
             2 => {
                 print!("\nType a number to calculate the fibonacci sequence to: ");
                 io::stdout().flush().unwrap();
-                let fib_num = get_input();
+                let mut fib_num = get_input();
+                if fib_num > MAX_FIB_N {
+                    println!("\nInput larger than {}, calculating only Fibonacci {}.", MAX_FIB_N, MAX_FIB_N);
+                    fib_num = MAX_FIB_N;
+                }
+
+                println!("\nFibonacci {} = {}", fib_num, format_big(&fibonacci::fast_doubling(fib_num), mode));
+            }
 
-                println!("\nFibonacci {} = {}", fib_num, fibonacci(fib_num));
+            3..=5 => {
+                let constant = match number {
+                    3 => Constant::Pi,
+                    4 => Constant::E,
+                    _ => Constant::Sqrt2,
+                };
+                print!("\nType a number of digits to calculate {} to (up to {}): ", constant.name(), MAX_DIGITS);
+                io::stdout().flush().unwrap();
+                let mut digits = get_input();
+
+                // the big-number arithmetic is quadratic, so keep the wait reasonable
+                if digits > MAX_DIGITS {
+                    println!("\nInput larger than {}, printing to only {} digits.", MAX_DIGITS, MAX_DIGITS);
+                    digits = MAX_DIGITS;
+                }
+                let result = constant.digits(digits as usize);
+                println!("\n{} to {} digits = {}", constant.name(), digits, format_digits(&result.text, mode));
+                if result.checked > 0 {
+                    println!("First {} digits checked against the known value: {}",
+                        result.checked, if result.matches { "match" } else { "MISMATCH" });
+                }
             }
 
-            3 => {
-                print!("\nType a number of digits to calculate pi to (up to 15): ");
+            6 => {
+                print!("\nType a number of digits of Pi to stream (up to {}): ", MAX_STREAM_DIGITS);
+                io::stdout().flush().unwrap();
+                let mut digits = get_input();
+                if digits > MAX_STREAM_DIGITS {
+                    println!("\nInput larger than {}, streaming only {} digits.", MAX_STREAM_DIGITS, MAX_STREAM_DIGITS);
+                    digits = MAX_STREAM_DIGITS;
+                }
+                print!("File to write the digits to (leave empty for the screen): ");
                 io::stdout().flush().unwrap();
-                let mut pi_num = get_input();
+                let path = get_line();
 
-                // im too lazy to code higher capacity stuff
-                if pi_num > 15{
-                    println!("\nInput larger than 15, printing to only 15 digits.");
-                    pi_num = 15;
+                // the screen gets short lines as the digits come; a file gets one long line
+                let result = if path.is_empty() {
+                    println!();
+                    spigot::stream(digits as usize, &mut io::stdout().lock(), Some(STREAM_LINE))
+                } else {
+                    match File::create(&path) {
+                        Ok(file) => spigot::stream(digits as usize, &mut BufWriter::new(file), None),
+                        Err(e) => Err(e),
+                    }
+                };
+                match result {
+                    Ok(time) => {
+                        let secs = time.as_secs_f64();
+                        println!("\nStreamed {} digits in {:.2} s ({:.0} digits/s)", digits, secs, digits as f64 / secs.max(1e-9));
+                        if !path.is_empty() {
+                            println!("Digits written to {}", path);
+                        }
+                    }
+                    Err(e) => println!("\nCould not write the digits: {}", e),
                 }
-                println!("\nPi to {} digits = {}", pi_num, find_pi(pi_num));
             }
 
-            4 => break,
+            7 => fibonacci_menu(mode),
+
+            8 => {
+                println!("\nType a recurrence and its first terms, e.g. a(n) = 5a(n-1) - 6a(n-2), a0 = 1, a1 = 4");
+                match recurrence::parse(&get_line()) {
+                    Ok(lin) => recurrence::solve(&lin),
+                    Err(e) => println!("\n{}", e),
+                }
+            }
+
+            9 => combinatorics_menu(mode),
+
+            10 => {
+                print!("\nShow big results as 1. full digits, 2. truncated, 3. digit count only: ");
+                io::stdout().flush().unwrap();
+                mode = match get_input() {
+                    1 => OutputMode::Full,
+                    2 => OutputMode::Truncated,
+                    3 => OutputMode::DigitCount,
+                    _ => {
+                        println!("\nNot a valid user input, keeping {}.", mode.name());
+                        mode
+                    }
+                };
+            }
+
+            11 => break,
             
             _ => {
                 println!("\nNot a valid user input.");
@@ -78,53 +227,264 @@ fn get_input() -> u64 {
 
 }
 
+// like get_input, but the number may be negative
+fn get_signed_input() -> i64 {
+    loop {
+        match get_line().parse::<i64>() {
+            Ok(num) => return num,
+            Err(_) => println!("That was not a valid whole number!"),
+        }
+    }
+}
+
+// read a line of text, trimmed; unlike get_input an empty line is allowed
+fn get_line() -> String {
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
+    input.trim().to_string()
+}
+
+// print a big result according to the display mode, with its length when that helps
+fn format_big(n: &BigUint, mode: OutputMode) -> String {
+    format_digits(&n.to_string(), mode)
+}
+
+// same for a number already written out, such as "3.14159..."
+fn format_digits(s: &str, mode: OutputMode) -> String {
+    let digits = s.chars().filter(char::is_ascii_digit).count();
+    match mode {
+        OutputMode::DigitCount => format!("[{} digit{}]", digits, if digits == 1 { "" } else { "s" }),
+        OutputMode::Truncated if digits > TRUNCATE_OVER => {
+            format!("{}...{} ({} digits)", &s[..SHOWN_DIGITS], &s[s.len() - SHOWN_DIGITS..], digits)
+        }
+        _ if digits > TRUNCATE_OVER => format!("{} ({} digits)", s, digits),
+        _ => s.to_string(),
+    }
+}
+
 // factorial algorithm from https://programming-idioms.org/idiom/31/recursive-factorial-simple/450/rust 
-fn factorial(num: u64) -> u64 {
-    match num {
-        0 | 1 => 1,
-        _ => factorial(num - 1) * num,
+// now a loop over big integers, since u64 overflows at 21! and deep recursion overflows the stack
+fn factorial(num: u64) -> BigUint {
+    let mut result = BigUint::from_u64(1);
+    for k in 2..=num {
+        result = match u32::try_from(k) {
+            Ok(small) if small < 1_000_000_000 => result.mul_small(small),
+            _ => &result * &BigUint::from_u64(k),
+        };
     }
+    result
 }
 
-// fibonacci sequence algorithm from https://benjaminbrandt.com/fibonacci-in-rust/
-fn fibonacci(n: u64) -> u64 {
-    let mut a = 1;
-    let mut b = 0;
-    let mut count = 0;
-    
-    while count < n {
-        let tmp = a + b;
-        b = a;
-        a = tmp;
-        count += 1;
+// the extra Fibonacci options, all built on the fibonacci module
+fn fibonacci_menu(mode: OutputMode) {
+    println!("\n1. Lucas number L(n)
+2. Term of your own recurrence a(n) = p a(n-1) + q a(n-2)
+3. Fibonacci, Lucas or your recurrence mod m, for n up to 10^18
+4. Pisano period of m (the cycle length of Fibonacci mod m)
+5. Benchmark: fast doubling vs matrix power vs the plain loop");
+    match get_input() {
+        1 => {
+            print!("\nType n: ");
+            io::stdout().flush().unwrap();
+            let mut n = get_input();
+            if n > MAX_FIB_N {
+                println!("\nInput larger than {}, calculating only L({}).", MAX_FIB_N, MAX_FIB_N);
+                n = MAX_FIB_N;
+            }
+            println!("\nL({}) = {}", n, format_big(&fibonacci::lucas(n), mode));
+        }
+
+        2 => {
+            let r = get_recurrence();
+            // the terms grow no faster than (|p| + |q|)^n, which bounds their digits
+            let growth = ((r.p.unsigned_abs() as f64) + (r.q.unsigned_abs() as f64)).log10().max(0.3);
+            let limit = (MAX_TERM_DIGITS / growth) as u64;
+            print!("Type n (up to {}): ", limit);
+            io::stdout().flush().unwrap();
+            let mut n = get_input();
+            if n > limit {
+                println!("\nInput larger than {}, calculating only a({}).", limit, limit);
+                n = limit;
+            }
+            println!("\n{}\na({}) = {}", r.describe(), n, format_digits(&r.term(n).to_string(), mode));
+        }
+
+        3 => {
+            print!("\nUse 1. Fibonacci, 2. Lucas, 3. your own recurrence: ");
+            io::stdout().flush().unwrap();
+            let r = match get_input() {
+                1 => Recurrence::FIBONACCI,
+                2 => Recurrence::LUCAS,
+                3 => get_recurrence(),
+                _ => {
+                    println!("\nNot a valid user input.");
+                    return;
+                }
+            };
+            print!("Type n (up to 10^18): ");
+            io::stdout().flush().unwrap();
+            let n = get_input().min(MAX_MOD_N);
+            let m = get_modulus();
+            println!("\n{}\na({}) mod {} = {}", r.describe(), n, m, r.term_mod(n, m));
+        }
+
+        4 => {
+            let m = get_modulus();
+            let start = Instant::now();
+            let period = fibonacci::pisano(m);
+            println!("\nPisano period of {} = {} (found in {:.3} s)", m, period, start.elapsed().as_secs_f64());
+            println!("so F(n) mod {} = F(n mod {}) mod {}", m, period, m);
+        }
+
+        5 => {
+            print!("\nType n to time F(n) with each method (up to {}): ", MAX_FIB_N);
+            io::stdout().flush().unwrap();
+            let n = get_input().min(MAX_FIB_N);
+            let (f, timings) = fibonacci::benchmark(n);
+            println!("\nF({}) = {}", n, format_big(&f, mode));
+            for t in timings {
+                match t.time {
+                    Some(time) => println!("  {:<15} {:>10.3} ms  {}", t.method, time.as_secs_f64() * 1000.0,
+                        if t.agrees { "same result" } else { "DIFFERENT RESULT" }),
+                    None => println!("  {:<15} skipped, too slow for this n", t.method),
+                }
+            }
+        }
+
+        _ => println!("\nNot a valid user input."),
     }
-    
-    b
 }
 
-// https://en.wikipedia.org/wiki/Gauss%E2%80%93Legendre_algorithm 
-fn find_pi(n: u64) -> f64{
-    let mut a = 1.0;
-    let mut b = 1.0 / 2.0_f64.sqrt();
-    let mut p = 1.0;
-    let mut t = 1.0/4.0;
-
-    for _ in 0..10{
-        let a_next = (a + b)/ 2.0;
-        let b_next = (a * b).sqrt();
-        let p_next = 2.0 * p;
-        let t_next = t - p * (a_next - a).powf(2.0);
-
-        a = a_next;
-        b = b_next;
-        p = p_next;
-        t = t_next;
+// the counting functions, all exact with big integers
+fn combinatorics_menu(mode: OutputMode) {
+    println!("\n1. Binomial coefficient C(n, k)
+2. Multinomial coefficient (k1 + k2 + ...)! / (k1! k2! ...)
+3. Permutations P(n, r) without repetition
+4. Permutations with repetition n^r
+5. Catalan number
+6. Stirling number of the first kind [n, k] (unsigned)
+7. Stirling number of the second kind {{n, k}}
+8. Bell number B(n)
+9. Partitions p(n)
+10. Derangements !n
+11. Pascal's triangle
+12. Stirling triangles of both kinds");
+    let choice = get_input();
+    let print = |name: String, value: BigUint| println!("\n{} = {}", name, format_big(&value, mode));
+    match choice {
+        1 => {
+            let n = ask("n");
+            let k = ask_capped("k", MAX_COMB_N);
+            print(format!("C({}, {})", n, k), combinatorics::binomial(n, k));
+        }
+
+        2 => {
+            print!("\nType the group sizes k1, k2, ... separated by commas: ");
+            io::stdout().flush().unwrap();
+            let parts: Result<Vec<u64>, _> = get_line().split([',', ' ']).filter(|p| !p.is_empty()).map(str::parse).collect();
+            match parts {
+                // a checked sum, so that huge sizes are refused instead of overflowing
+                Ok(parts) if !parts.is_empty()
+                    && parts.iter().try_fold(0u64, |sum, &k| sum.checked_add(k)).is_some_and(|total| total <= MAX_COMB_N) => {
+                    let shown: Vec<String> = parts.iter().map(u64::to_string).collect();
+                    print(format!("({})", shown.join(", ")), combinatorics::multinomial(&parts));
+                }
+                Ok(parts) if !parts.is_empty() => println!("\nThe sizes must add up to at most {}.", MAX_COMB_N),
+                _ => println!("\nThat was not a list of whole numbers!"),
+            }
+        }
+
+        3 => {
+            let n = ask("n");
+            let r = ask_capped("r", MAX_COMB_N);
+            print(format!("P({}, {})", n, r), combinatorics::permutations(n, r));
+        }
+
+        4 => {
+            let n = ask("n");
+            let r = ask_capped("r", MAX_COMB_N);
+            print(format!("{}^{}", n, r), combinatorics::permutations_with_repetition(n, r));
+        }
+
+        5 => {
+            let n = ask_capped("n", MAX_COMB_N);
+            print(format!("Catalan {}", n), combinatorics::catalan(n));
+        }
+
+        6 | 7 => {
+            let kind = if choice == 6 { Stirling::First } else { Stirling::Second };
+            let n = ask_capped("n", MAX_STIRLING_N);
+            let k = ask("k");
+            let value = kind.row(n).get(k as usize).cloned().unwrap_or(BigUint::zero());
+            print(kind.notation(n, k), value);
+        }
+
+        8 => {
+            let n = ask_capped("n", MAX_STIRLING_N);
+            print(format!("B({})", n), combinatorics::bell(n));
+        }
+
+        9 => {
+            let n = ask_capped("n", MAX_PARTITION_N);
+            print(format!("p({})", n), combinatorics::partitions(n));
+        }
+
+        10 => {
+            let n = ask_capped("n", MAX_COMB_N);
+            print(format!("!{}", n), combinatorics::derangements(n));
+        }
+
+        11 => {
+            let n = ask_capped("the last row", MAX_TABLE_ROWS);
+            combinatorics::print_triangle("Pascal's triangle: C(n, k)", &combinatorics::pascal(n));
+        }
+
+        12 => {
+            let n = ask_capped("the last row", MAX_TABLE_ROWS);
+            for kind in [Stirling::First, Stirling::Second] {
+                combinatorics::print_triangle(kind.name(), &kind.triangle(n));
+            }
+        }
+
+        _ => println!("\nNot a valid user input."),
+    }
+}
+
+fn ask(name: &str) -> u64 {
+    print!("Type {}: ", name);
+    io::stdout().flush().unwrap();
+    get_input()
+}
+
+// like ask, but larger inputs are brought down to `max`
+fn ask_capped(name: &str, max: u64) -> u64 {
+    let value = ask(&format!("{} (up to {})", name, max));
+    if value > max {
+        println!("\nInput larger than {}, using {}.", max, max);
+        return max;
     }
+    value
+}
 
-    // final computation
-    let pi = ((a+b).powf(2.0))/(4.0 * t);
+// ask for p, q, a(0) and a(1) of a second-order recurrence
+fn get_recurrence() -> Recurrence {
+    println!("\nRecurrence a(n) = p a(n-1) + q a(n-2)");
+    let ask = |name: &str| {
+        print!("Type {}: ", name);
+        io::stdout().flush().unwrap();
+        get_signed_input()
+    };
+    Recurrence { p: ask("p"), q: ask("q"), a0: ask("a(0)"), a1: ask("a(1)") }
+}
 
-    // trim result to inputted amount
-    let result = f64::trunc(pi * (10.0_f64.powf(n as f64))) / (10.0_f64.powf(n as f64));
-    result
-}
\ No newline at end of file
+fn get_modulus() -> u64 {
+    loop {
+        print!("Type the modulus m (1 to 10^18): ");
+        io::stdout().flush().unwrap();
+        let m = get_input();
+        if (1..=MAX_MOD_N).contains(&m) {
+            return m;
+        }
+        println!("The modulus must be between 1 and 10^18.");
+    }
+}
@@ -0,0 +1,921 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::bigint::{BigInt, BigUint};
+
+// most terms on the right-hand side the solver takes on
+const MAX_ORDER: usize = 10;
+// terms compared between the closed form and the recurrence itself
+const CHECK_TERMS: usize = 40;
+// terms of the sequence shown before the closed form
+const SHOWN_TERMS: usize = 10;
+
+/// a(n) = c1 a(n-1) + ... + ck a(n-k), starting from a(0) .. a(k-1)
+pub struct Linear {
+    pub coefficients: Vec<i64>, // c1 .. ck
+    pub initial: Vec<i64>,
+}
+
+// ----------------------------------------------------------------------------
+// READING A RECURRENCE
+// ----------------------------------------------------------------------------
+
+/// Read a recurrence and its first terms written like
+/// `a(n) = 5a(n-1) - 6a(n-2), a0 = 1, a1 = 4`
+pub fn parse(text: &str) -> Result<Linear, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut parts = text.split(',');
+    let equation = parts.next().unwrap_or("");
+    let mut rest = equation.strip_prefix("a(n)=").ok_or("The recurrence must start with a(n) =")?;
+
+    let mut coefficients = vec![0i64; MAX_ORDER];
+    let mut order = 0;
+    let mut first = true;
+    while !rest.is_empty() {
+        if !first && !rest.starts_with(['+', '-']) {
+            return Err(format!("Expected + or - before \"{}\"", rest));
+        }
+        let (c, index, after) = parse_term(rest).ok_or_else(|| format!("Could not read the term at \"{}\"", rest))?;
+        if index == 0 || index > MAX_ORDER {
+            return Err(format!("Terms must be a(n-1) to a(n-{})", MAX_ORDER));
+        }
+        coefficients[index - 1] = coefficients[index - 1].checked_add(c).ok_or("Coefficient too large")?;
+        order = order.max(index);
+        rest = after;
+        first = false;
+    }
+    // the order is set by the terms written, even one with a zero coefficient
+    if order == 0 {
+        return Err("The right-hand side needs at least one term a(n-k)".to_string());
+    }
+    coefficients.truncate(order);
+
+    let mut initial = vec![None; coefficients.len()];
+    for part in parts {
+        let (index, value) = parse_initial(part).ok_or_else(|| format!("Could not read the first term \"{}\"", part))?;
+        if index >= initial.len() {
+            return Err(format!("An order-{} recurrence starts from a(0) to a({}); a({}) is not needed",
+                initial.len(), initial.len() - 1, index));
+        }
+        initial[index] = Some(value);
+    }
+    let initial = initial.iter().enumerate()
+        .map(|(i, v)| v.ok_or(format!("Missing the first term a({})", i)))
+        .collect::<Result<Vec<i64>, String>>()?;
+    Ok(Linear { coefficients, initial })
+}
+
+// one term like -6a(n-2) or +a(n-1), returning the coefficient, k and the rest
+fn parse_term(s: &str) -> Option<(i64, usize, &str)> {
+    let (negative, s) = match s.as_bytes().first()? {
+        b'-' => (true, &s[1..]),
+        b'+' => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let c: i64 = if digits == 0 { 1 } else { s[..digits].parse().ok()? };
+    let s = &s[digits..];
+    let s = s.strip_prefix('*').unwrap_or(s).strip_prefix("a(n-")?;
+    let close = s.find(')')?;
+    let index = s[..close].parse().ok()?;
+    Some((if negative { -c } else { c }, index, &s[close + 1..]))
+}
+
+// a first term like a0=1, a(0)=1 or a_0=1
+fn parse_initial(s: &str) -> Option<(usize, i64)> {
+    let (name, value) = s.split_once('=')?;
+    let index = name.strip_prefix('a')?.trim_start_matches('_').trim_start_matches('(').trim_end_matches(')');
+    Some((index.parse().ok()?, value.parse().ok()?))
+}
+
+impl Linear {
+    /// The characteristic polynomial x^k - c1 x^(k-1) - ... - ck, highest power first
+    pub fn polynomial(&self) -> Vec<i128> {
+        let mut poly = vec![1];
+        poly.extend(self.coefficients.iter().map(|&c| -(c as i128)));
+        poly
+    }
+
+    /// The first `count` terms, straight from the recurrence
+    pub fn iterate(&self, count: usize) -> Vec<BigInt> {
+        let mut terms: Vec<BigInt> = self.initial.iter().map(|&a| BigInt::from_i64(a)).collect();
+        while terms.len() < count {
+            let n = terms.len();
+            let mut next = BigInt::from_i64(0);
+            for (i, &c) in self.coefficients.iter().enumerate() {
+                next = &next + &(&terms[n - 1 - i] * &BigInt::from_i64(c));
+            }
+            terms.push(next);
+        }
+        terms.truncate(count);
+        terms
+    }
+}
+
+fn show_polynomial(poly: &[i128]) -> String {
+    let degree = poly.len() - 1;
+    let mut s = String::new();
+    for (i, &c) in poly.iter().enumerate() {
+        if c == 0 {
+            continue;
+        }
+        let power = degree - i;
+        let sign = if c < 0 { " - " } else { " + " };
+        if s.is_empty() {
+            s.push_str(if c < 0 { "-" } else { "" });
+        } else {
+            s.push_str(sign);
+        }
+        let x = match power {
+            0 => String::new(),
+            1 => "x".to_string(),
+            _ => format!("x^{}", power),
+        };
+        if c.abs() != 1 || power == 0 {
+            s.push_str(&c.abs().to_string());
+        }
+        s.push_str(&x);
+    }
+    s
+}
+
+// ----------------------------------------------------------------------------
+// COMPLEX NUMBERS
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn real(re: f64) -> Complex {
+        Complex { re, im: 0.0 }
+    }
+
+    fn polar(r: f64, theta: f64) -> Complex {
+        Complex { re: r * theta.cos(), im: r * theta.sin() }
+    }
+
+    fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    fn powi(self, n: usize) -> Complex {
+        let mut result = Complex::real(1.0);
+        for _ in 0..n {
+            result = result * self;
+        }
+        result
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, o: Complex) -> Complex {
+        Complex { re: self.re + o.re, im: self.im + o.im }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, o: Complex) -> Complex {
+        Complex { re: self.re - o.re, im: self.im - o.im }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, o: Complex) -> Complex {
+        Complex { re: self.re * o.re - self.im * o.im, im: self.re * o.im + self.im * o.re }
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, o: Complex) -> Complex {
+        let d = o.re * o.re + o.im * o.im;
+        Complex { re: (self.re * o.re + self.im * o.im) / d, im: (self.im * o.re - self.re * o.im) / d }
+    }
+}
+
+// a number to ten significant places or so, without trailing zeros
+fn number(x: f64) -> String {
+    if x != 0.0 && !(1e-6..1e12).contains(&x.abs()) {
+        return format!("{:.9e}", x);
+    }
+    let s = format!("{:.10}", x);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+fn show_complex(z: Complex) -> String {
+    if z.im == 0.0 {
+        return number(z.re);
+    }
+    let im = if z.im.abs() == 1.0 { "i".to_string() } else { format!("{}i", number(z.im.abs())) };
+    match (z.re == 0.0, z.im < 0.0) {
+        (true, true) => format!("-{}", im),
+        (true, false) => im,
+        (false, true) => format!("{} - {}", number(z.re), im),
+        (false, false) => format!("{} + {}", number(z.re), im),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// ROOTS OF THE CHARACTERISTIC POLYNOMIAL
+// ----------------------------------------------------------------------------
+
+/// A root with its multiplicity; integer roots and roots of a leftover
+/// quadratic are also known exactly
+struct Root {
+    value: Complex,
+    multiplicity: usize,
+    integer: Option<i128>,
+    exact: Option<String>,
+}
+
+fn float(poly: &[i128]) -> Vec<f64> {
+    poly.iter().map(|&c| c as f64).collect()
+}
+
+// all roots at once by the Durand-Kerner iteration, for a monic polynomial
+fn durand_kerner(poly: &[f64]) -> Vec<Complex> {
+    let degree = poly.len() - 1;
+    let eval = |z: Complex| poly.iter().fold(Complex::real(0.0), |acc, &c| acc * z + Complex::real(c));
+    // start spread around a circle that contains every root (Cauchy's bound)
+    let bound = 1.0 + poly[1..].iter().fold(0.0f64, |m, c| m.max(c.abs()));
+    let mut z: Vec<Complex> = (0..degree).map(|i| Complex::polar(bound, 0.4 + 2.0 * PI * i as f64 / degree as f64)).collect();
+    for _ in 0..5000 {
+        let mut change = 0.0f64;
+        for i in 0..degree {
+            let mut denominator = Complex::real(1.0);
+            for j in 0..degree {
+                if j != i {
+                    denominator = denominator * (z[i] - z[j]);
+                }
+            }
+            let step = eval(z[i]) / denominator;
+            z[i] = z[i] - step;
+            change = change.max(step.abs() / z[i].abs().max(1.0));
+        }
+        if change < 1e-15 {
+            break;
+        }
+    }
+    z
+}
+
+fn differentiate(poly: &[f64]) -> Vec<f64> {
+    let degree = poly.len() - 1;
+    poly[..degree].iter().enumerate().map(|(i, &c)| c * (degree - i) as f64).collect()
+}
+
+// polish a root by Newton's method, which converges fast next to a simple root
+fn newton(poly: &[f64], mut z: Complex) -> Complex {
+    let slope = differentiate(poly);
+    let eval = |p: &[f64], z: Complex| p.iter().fold(Complex::real(0.0), |acc, &c| acc * z + Complex::real(c));
+    for _ in 0..50 {
+        let d = eval(&slope, z);
+        if d.abs() == 0.0 {
+            break;
+        }
+        let step = eval(poly, z) / d;
+        z = z - step;
+        if step.abs() <= 1e-16 * z.abs().max(1.0) {
+            break;
+        }
+    }
+    z
+}
+
+// the roots of a real polynomial are real or come in conjugate pairs, so snap
+// the nearly real ones and make each pair exact conjugates of their average
+fn conjugate_pairs(z: &mut [Complex]) {
+    for w in z.iter_mut().filter(|w| w.im.abs() < 1e-6 * w.abs().max(1.0)) {
+        w.im = 0.0;
+    }
+    for i in 0..z.len() {
+        if z[i].im <= 0.0 {
+            continue;
+        }
+        let mirror = Complex { re: z[i].re, im: -z[i].im };
+        let partner = (0..z.len()).filter(|&j| z[j].im < 0.0)
+            .min_by(|&a, &b| (z[a] - mirror).abs().total_cmp(&(z[b] - mirror).abs()));
+        if let Some(j) = partner {
+            let mean = Complex { re: (z[i].re + z[j].re) / 2.0, im: (z[i].im - z[j].im) / 2.0 };
+            z[i] = mean;
+            z[j] = Complex { re: mean.re, im: -mean.im };
+        }
+    }
+}
+
+// poly / (x - r) when r is an exact root, by synthetic division
+fn divide_exact(poly: &[i128], r: i128) -> Option<Vec<i128>> {
+    let mut quotient = Vec::with_capacity(poly.len() - 1);
+    let mut carry = 0i128;
+    for &c in poly {
+        carry = carry.checked_mul(r)?.checked_add(c)?;
+        quotient.push(carry);
+    }
+    (quotient.pop() == Some(0)).then_some(quotient)
+}
+
+// ----------------------------------------------------------------------------
+// SQUARE-FREE FACTORING
+// ----------------------------------------------------------------------------
+
+// polynomials here have integer coefficients, highest power first, with no
+// leading zeros (so the zero polynomial is empty); every step is checked and
+// gives up with None on overflow
+
+fn derivative(poly: &[i128]) -> Option<Vec<i128>> {
+    let degree = poly.len() - 1;
+    poly[..degree].iter().enumerate().map(|(i, &c)| c.checked_mul((degree - i) as i128)).collect()
+}
+
+// drop leading zeros and the content, and make the leading coefficient positive
+fn primitive(mut poly: Vec<i128>) -> Vec<i128> {
+    let zeros = poly.iter().take_while(|&&c| c == 0).count();
+    poly.drain(..zeros);
+    let content = poly.iter().fold(0, |g, &c| gcd(g, c));
+    let sign = if poly.first().is_some_and(|&c| c < 0) { -1 } else { 1 };
+    for c in poly.iter_mut() {
+        *c = sign * *c / content;
+    }
+    poly
+}
+
+// the remainder of a by b up to a constant factor, kept primitive
+fn remainder(a: &[i128], b: &[i128]) -> Option<Vec<i128>> {
+    let mut r = a.to_vec();
+    while r.len() >= b.len() {
+        // b[0] r - r[0] x^k b cancels the leading term
+        let mut next = Vec::with_capacity(r.len() - 1);
+        for i in 1..r.len() {
+            let cancel = if i < b.len() { r[0].checked_mul(b[i])? } else { 0 };
+            next.push(b[0].checked_mul(r[i])?.checked_sub(cancel)?);
+        }
+        r = primitive(next);
+    }
+    Some(r)
+}
+
+fn poly_gcd(a: &[i128], b: &[i128]) -> Option<Vec<i128>> {
+    let (mut a, mut b) = (primitive(a.to_vec()), primitive(b.to_vec()));
+    while !b.is_empty() {
+        let r = remainder(&a, &b)?;
+        a = b;
+        b = r;
+    }
+    Some(a)
+}
+
+// a / b when b divides a; by Gauss's lemma the quotient of two primitive
+// polynomials has integer coefficients
+fn divide(a: &[i128], b: &[i128]) -> Option<Vec<i128>> {
+    let mut r = a.to_vec();
+    let mut quotient = Vec::new();
+    while r.len() >= b.len() {
+        if r[0] % b[0] != 0 {
+            return None;
+        }
+        let q = r[0] / b[0];
+        for i in 1..b.len() {
+            r[i] = r[i].checked_sub(q.checked_mul(b[i])?)?;
+        }
+        r.remove(0);
+        quotient.push(q);
+    }
+    r.iter().all(|&c| c == 0).then_some(quotient)
+}
+
+/// Split the polynomial into square-free factors, each with the multiplicity
+/// its roots have: repeated gcds with the derivative give
+///   s_k = the product of the distinct factors of multiplicity at least k,
+/// and the roots of s_k / s_(k+1) are exactly those of multiplicity k
+fn squarefree_factors(poly: &[i128]) -> Option<Vec<(usize, Vec<i128>)>> {
+    let mut at_least = Vec::new();
+    let mut current = primitive(poly.to_vec());
+    while current.len() > 1 {
+        let g = poly_gcd(&current, &derivative(&current)?)?;
+        at_least.push(divide(&current, &g)?);
+        current = g;
+    }
+    let mut factors = Vec::new();
+    for (k, s) in at_least.iter().enumerate() {
+        let exactly = match at_least.get(k + 1) {
+            Some(next) => divide(s, next)?,
+            None => s.clone(),
+        };
+        if exactly.len() > 1 {
+            factors.push((k + 1, exactly));
+        }
+    }
+    Some(factors)
+}
+
+// ----------------------------------------------------------------------------
+// FINDING THE ROOTS
+// ----------------------------------------------------------------------------
+
+// integer roots, exactly: the numeric roots suggest the candidates, loosely,
+// since a repeated root comes out with a visible error; returns what is left
+fn integer_roots(poly: &[i128]) -> (Vec<Root>, Vec<i128>) {
+    let mut candidates: Vec<i128> = durand_kerner(&float(poly)).iter()
+        .filter(|z| z.im.abs() < 0.5)
+        .map(|z| z.re.round() as i128)
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    let mut rest = poly.to_vec();
+    let mut found = Vec::new();
+    for r in candidates {
+        let mut multiplicity = 0;
+        while rest.len() > 1 && let Some(q) = divide_exact(&rest, r) {
+            rest = q;
+            multiplicity += 1;
+        }
+        if multiplicity > 0 {
+            found.push(Root { value: Complex::real(r as f64), multiplicity, integer: Some(r), exact: None });
+        }
+    }
+    (found, rest)
+}
+
+// the roots of a square-free factor, all of them simple
+fn simple_roots(poly: &[i128]) -> Vec<Root> {
+    let (mut found, rest) = integer_roots(poly);
+    if rest.len() == 3 && rest[0] == 1 && let Some(pair) = quadratic(rest[1], rest[2]) {
+        found.extend(pair);
+    } else if rest.len() > 1 {
+        let rest = float(&rest);
+        let mut z: Vec<Complex> = durand_kerner(&rest).into_iter().map(|z| newton(&rest, z)).collect();
+        conjugate_pairs(&mut z);
+        found.extend(z.into_iter().map(|value| Root { value, multiplicity: 1, integer: None, exact: None }));
+    }
+    found
+}
+
+// when the coefficients are too large to factor exactly: the numeric iteration
+// splits a repeated root into a tight cluster, so merge each cluster and refine
+// it on the derivative where the root is simple
+fn clustered_roots(poly: &[i128]) -> Vec<Root> {
+    let (mut found, rest) = integer_roots(poly);
+    if rest.len() == 3 && let Some(pair) = quadratic(rest[1], rest[2]) {
+        found.extend(pair);
+        return found;
+    }
+    if rest.len() < 2 {
+        return found;
+    }
+    let rest = float(&rest);
+    let mut clusters: Vec<(Complex, usize)> = Vec::new();
+    for z in durand_kerner(&rest) {
+        match clusters.iter_mut().find(|(c, _)| (*c - z).abs() < 1e-4 * z.abs().max(1.0)) {
+            Some((c, m)) => {
+                let k = *m as f64;
+                *c = Complex { re: (c.re * k + z.re) / (k + 1.0), im: (c.im * k + z.im) / (k + 1.0) };
+                *m += 1;
+            }
+            None => clusters.push((z, 1)),
+        }
+    }
+    // a root of multiplicity m is a simple root of the (m-1)th derivative
+    let mut values: Vec<Complex> = clusters.iter().map(|&(c, m)| {
+        let derived = (1..m).fold(rest.clone(), |p, _| differentiate(&p));
+        newton(&derived, c)
+    }).collect();
+    conjugate_pairs(&mut values);
+    found.extend(values.into_iter().zip(&clusters)
+        .map(|(value, &(_, multiplicity))| Root { value, multiplicity, integer: None, exact: None }));
+    found
+}
+
+fn roots(poly: &[i128]) -> Vec<Root> {
+    let mut found = Vec::new();
+    match squarefree_factors(poly) {
+        // the roots of each factor are simple, so they come out accurately
+        Some(factors) => {
+            for (multiplicity, factor) in factors {
+                found.extend(simple_roots(&factor).into_iter().map(|r| Root { multiplicity, ..r }));
+            }
+        }
+        None => found = clustered_roots(poly),
+    }
+    // largest roots first, since they dominate the closed form
+    found.sort_by(|a, b| b.value.abs().total_cmp(&a.value.abs()).then(b.value.re.total_cmp(&a.value.re))
+        .then(b.value.im.total_cmp(&a.value.im)));
+    found
+}
+
+// roots of x^2 + bx + c without integer roots: (-b +- sqrt(b^2 - 4c)) / 2
+fn quadratic(b: i128, c: i128) -> Option<Vec<Root>> {
+    let d = b.checked_mul(b)?.checked_sub(c.checked_mul(4)?)?;
+    // write sqrt(|d|) as s sqrt(free), taking out the small square factors
+    let (mut s, mut free) = (1i128, d.abs());
+    let mut p = 2;
+    while p * p <= free && p < 100_000 {
+        while free % (p * p) == 0 {
+            free /= p * p;
+            s *= p;
+        }
+        p += 1;
+    }
+    // (-b +- s sqrt(free)) / 2, halved when -b and s are both even
+    let (middle, s, halved) = if b % 2 == 0 && s % 2 == 0 { (-b / 2, s / 2, true) } else { (-b, s, false) };
+    let complex = d < 0;
+    let root = if free == 1 { String::new() } else { format!("sqrt({})", free) };
+    let scale = if s == 1 && (complex || !root.is_empty()) { String::new() } else { s.to_string() };
+    let surd = format!("{}{}{}", scale, root, if complex { "i" } else { "" });
+    let show = |sign: &str| {
+        let body = match (middle, sign) {
+            (0, "-") => format!("-{}", surd),
+            (0, _) => surd.clone(),
+            _ => format!("{} {} {}", middle, sign, surd),
+        };
+        match (halved, middle) {
+            (true, _) => body,
+            (false, 0) => format!("{}/2", body),
+            (false, _) => format!("({})/2", body),
+        }
+    };
+
+    let (re, spread) = (-b as f64 / 2.0, (d.abs() as f64).sqrt() / 2.0);
+    let (plus, minus) = if complex {
+        (Complex { re, im: spread }, Complex { re, im: -spread })
+    } else {
+        // the smaller root as c over the larger, which avoids cancelling digits
+        let big = if re >= 0.0 { re + spread } else { re - spread };
+        let small = c as f64 / big;
+        if re >= 0.0 { (Complex::real(big), Complex::real(small)) } else { (Complex::real(small), Complex::real(big)) }
+    };
+    Some(vec![
+        Root { value: plus, multiplicity: 1, integer: None, exact: Some(show("+")) },
+        Root { value: minus, multiplicity: 1, integer: None, exact: Some(show("-")) },
+    ])
+}
+
+fn show_root(root: &Root) -> String {
+    let value = match (&root.integer, &root.exact) {
+        (Some(r), _) => r.to_string(),
+        (None, Some(exact)) if *exact == show_complex(root.value) => exact.clone(),
+        (None, Some(exact)) => format!("{} = {}", exact, show_complex(root.value)),
+        (None, None) => show_complex(root.value),
+    };
+    match root.multiplicity {
+        1 => value,
+        m => format!("{} (multiplicity {})", value, m),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// FRACTIONS (for exact closed forms)
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq)]
+struct Fraction {
+    num: i128,
+    den: i128, // always positive, in lowest terms with num
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+// every operation is checked, since exact solving falls back to floats on overflow
+impl Fraction {
+    fn new(num: i128, den: i128) -> Option<Fraction> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num, den);
+        let sign = if den < 0 { -1 } else { 1 };
+        Some(Fraction { num: sign * num / g, den: sign * den / g })
+    }
+
+    fn int(n: i128) -> Fraction {
+        Fraction { num: n, den: 1 }
+    }
+
+    fn add(self, o: Fraction) -> Option<Fraction> {
+        let num = self.num.checked_mul(o.den)?.checked_add(o.num.checked_mul(self.den)?)?;
+        Fraction::new(num, self.den.checked_mul(o.den)?)
+    }
+
+    fn mul(self, o: Fraction) -> Option<Fraction> {
+        Fraction::new(self.num.checked_mul(o.num)?, self.den.checked_mul(o.den)?)
+    }
+
+    fn div(self, o: Fraction) -> Option<Fraction> {
+        Fraction::new(self.num.checked_mul(o.den)?, self.den.checked_mul(o.num)?)
+    }
+
+    fn neg(self) -> Fraction {
+        Fraction { num: -self.num, den: self.den }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// CLOSED FORM
+// ----------------------------------------------------------------------------
+
+// a(n) is a sum of n^j r^n over each root r and j below its multiplicity;
+// a root 0 instead contributes terms that are 1 only at n = j
+fn basis(r: Complex, j: usize, n: usize) -> Complex {
+    if r.re == 0.0 && r.im == 0.0 {
+        return Complex::real(if n == j { 1.0 } else { 0.0 });
+    }
+    Complex::real((n as f64).powi(j as i32)) * r.powi(n)
+}
+
+fn basis_exact(r: i128, j: usize, n: usize) -> Option<i128> {
+    if r == 0 {
+        return Some((n == j) as i128);
+    }
+    (n as i128).checked_pow(j as u32)?.checked_mul(r.checked_pow(n as u32)?)
+}
+
+// Gaussian elimination on the rows n = 0 .. k-1 of basis values = a(n)
+fn solve_exact(roots: &[Root], initial: &[i64]) -> Option<Vec<Fraction>> {
+    let unknowns: Vec<(i128, usize)> = roots.iter()
+        .flat_map(|r| (0..r.multiplicity).map(move |j| (r.integer.unwrap(), j)))
+        .collect();
+    let k = unknowns.len();
+    let mut rows = Vec::with_capacity(k);
+    for (n, &a) in initial.iter().enumerate() {
+        let mut row = Vec::with_capacity(k + 1);
+        for &(r, j) in &unknowns {
+            row.push(Fraction::int(basis_exact(r, j, n)?));
+        }
+        row.push(Fraction::int(a as i128));
+        rows.push(row);
+    }
+    for col in 0..k {
+        let pivot = (col..k).find(|&i| rows[i][col].num != 0)?;
+        rows.swap(col, pivot);
+        let pivot_row = rows[col].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != col && row[col].num != 0 {
+                let factor = row[col].div(pivot_row[col])?;
+                for (x, &p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *x = x.add(p.mul(factor)?.neg())?;
+                }
+            }
+        }
+    }
+    (0..k).map(|i| rows[i][k].div(rows[i][i])).collect()
+}
+
+fn solve_float(roots: &[Root], initial: &[i64]) -> Vec<Complex> {
+    let unknowns: Vec<(Complex, usize)> = roots.iter()
+        .flat_map(|r| (0..r.multiplicity).map(move |j| (r.value, j)))
+        .collect();
+    let k = unknowns.len();
+    let mut rows: Vec<Vec<Complex>> = initial.iter().enumerate()
+        .map(|(n, &a)| unknowns.iter().map(|&(r, j)| basis(r, j, n)).chain([Complex::real(a as f64)]).collect())
+        .collect();
+    for col in 0..k {
+        // partial pivoting keeps the elimination stable
+        let pivot = (col..k).max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs())).unwrap();
+        rows.swap(col, pivot);
+        let pivot_row = rows[col].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != col {
+                let factor = row[col] / pivot_row[col];
+                for (x, &p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *x = *x - p * factor;
+                }
+            }
+        }
+    }
+    (0..k).map(|i| rows[i][k] / rows[i][i]).collect()
+}
+
+// "n^j" as a factor
+fn power_of_n(j: usize) -> String {
+    match j {
+        0 => String::new(),
+        1 => "n".to_string(),
+        _ => format!("n^{}", j),
+    }
+}
+
+// join signed pieces into "a - b + c"
+fn join(pieces: &[(bool, String)]) -> String {
+    if pieces.is_empty() {
+        return "0".to_string();
+    }
+    let mut s = String::new();
+    for (i, (negative, text)) in pieces.iter().enumerate() {
+        match (i, negative) {
+            (0, true) => s.push('-'),
+            (0, false) => {}
+            (_, true) => s.push_str(" - "),
+            (_, false) => s.push_str(" + "),
+        }
+        s.push_str(text);
+    }
+    s
+}
+
+// a product of factors, leaving out the empty ones and a leading 1
+fn product(factors: &[String]) -> String {
+    let parts: Vec<&str> = factors.iter().map(String::as_str).filter(|f| !f.is_empty()).collect();
+    let parts: Vec<&str> = match parts.as_slice() {
+        ["1", rest @ ..] if !rest.is_empty() => rest.to_vec(),
+        _ => parts,
+    };
+    if parts.is_empty() { "1".to_string() } else { parts.join("*") }
+}
+
+fn exact_form(roots: &[Root], coefficients: &[Fraction]) -> String {
+    let mut pieces = Vec::new();
+    let mut next = coefficients.iter();
+    for root in roots {
+        let r = root.integer.unwrap();
+        let terms: Vec<(usize, Fraction)> = (0..root.multiplicity)
+            .map(|j| (j, *next.next().unwrap()))
+            .filter(|(_, c)| c.num != 0)
+            .collect();
+        let magnitude = |c: Fraction| match c.den {
+            1 => c.num.abs().to_string(),
+            d => format!("({}/{})", c.num.abs(), d),
+        };
+        if r == 0 {
+            for (j, c) in terms {
+                pieces.push((c.num < 0, product(&[magnitude(c), format!("[n = {}]", j)])));
+            }
+            continue;
+        }
+        let power = match r {
+            1 => String::new(),
+            r if r < 0 => format!("({})^n", r),
+            r => format!("{}^n", r),
+        };
+        if terms.len() == 1 || power.is_empty() {
+            for (j, c) in terms {
+                pieces.push((c.num < 0, product(&[magnitude(c), power_of_n(j), power.clone()])));
+            }
+        } else if !terms.is_empty() {
+            let inner: Vec<(bool, String)> = terms.iter()
+                .map(|&(j, c)| (c.num < 0, product(&[magnitude(c), power_of_n(j)])))
+                .collect();
+            pieces.push((false, format!("({})*{}", join(&inner), power)));
+        }
+    }
+    join(&pieces)
+}
+
+fn numeric_form(roots: &[Root], coefficients: &[Complex]) -> String {
+    let mut pieces = Vec::new();
+    let mut next = coefficients.iter();
+    for root in roots {
+        let r = root.value;
+        let terms: Vec<(usize, Complex)> = (0..root.multiplicity).map(|j| (j, *next.next().unwrap())).collect();
+        // a conjugate pair adds up to a real cosine term; it is written at the upper root
+        if r.im < 0.0 {
+            continue;
+        }
+        for (j, c) in terms {
+            if r.im > 0.0 {
+                let (rho, theta) = (r.abs(), r.arg());
+                let growth = if (rho - 1.0).abs() < 1e-12 { String::new() } else { format!("{}^n", number(rho)) };
+                let phase = c.arg();
+                let cos = match number(phase.abs()).as_str() {
+                    "0" => format!("cos({}n)", number(theta)),
+                    shift => format!("cos({}n {} {})", number(theta), if phase < 0.0 { "-" } else { "+" }, shift),
+                };
+                pieces.push((false, product(&[number(2.0 * c.abs()), power_of_n(j), growth, cos])));
+            } else if r.re == 0.0 {
+                pieces.push((c.re < 0.0, product(&[number(c.re.abs()), format!("[n = {}]", j)])));
+            } else {
+                let power = if r.re < 0.0 { format!("({})^n", number(r.re)) } else { format!("{}^n", number(r.re)) };
+                pieces.push((c.re < 0.0, product(&[number(c.re.abs()), power_of_n(j), power])));
+            }
+        }
+    }
+    join(&pieces)
+}
+
+// ----------------------------------------------------------------------------
+// SOLVING
+// ----------------------------------------------------------------------------
+
+/// Print the characteristic polynomial, its roots and the closed form of a(n),
+/// then check the closed form against the recurrence
+pub fn solve(lin: &Linear) {
+    let poly = lin.polynomial();
+    let terms = lin.iterate(CHECK_TERMS);
+    let shown: Vec<String> = terms.iter().take(SHOWN_TERMS).map(|t| t.to_string()).collect();
+    println!("\nFirst terms: {}, ...", shown.join(", "));
+    println!("Characteristic polynomial: {}", show_polynomial(&poly));
+
+    let roots = roots(&poly);
+    let shown: Vec<String> = roots.iter().map(show_root).collect();
+    println!("Roots: {}", shown.join(", "));
+
+    // integer roots give rational coefficients, so the closed form can be exact
+    let exact = if roots.iter().all(|r| r.integer.is_some()) { solve_exact(&roots, &lin.initial) } else { None };
+    if let Some(coefficients) = exact {
+        println!("Closed form: a(n) = {}", exact_form(&roots, &coefficients));
+        let values: Option<Vec<String>> = (0..CHECK_TERMS).map(|n| exact_value(&roots, &coefficients, n)).collect();
+        let verdict = match values {
+            Some(v) if v.iter().zip(&terms).all(|(x, t)| *x == t.to_string()) => "all match",
+            Some(_) => "MISMATCH",
+            None => "numbers too large to check",
+        };
+        println!("Checked exactly against the recurrence for n = 0 to {}: {}", CHECK_TERMS - 1, verdict);
+        return;
+    }
+
+    let coefficients = solve_float(&roots, &lin.initial);
+    println!("Closed form (coefficients rounded): a(n) = {}", numeric_form(&roots, &coefficients));
+    // compare relative to the size of the terms, which can grow without bound
+    // past about 1e308 the terms or the closed form become infinite (or NaN),
+    // which would compare as no error at all
+    let mut worst = Some(0.0f64);
+    for (n, term) in terms.iter().enumerate() {
+        let mut value = Complex::real(0.0);
+        let mut next = coefficients.iter();
+        for root in &roots {
+            for j in 0..root.multiplicity {
+                value = value + *next.next().unwrap() * basis(root.value, j, n);
+            }
+        }
+        let actual: f64 = term.to_string().parse().unwrap();
+        let error = (value.re - actual).abs() / actual.abs().max(1.0);
+        worst = worst.filter(|_| error.is_finite() && actual.is_finite()).map(|w| w.max(error));
+    }
+    match worst {
+        Some(worst) => println!("Checked against the recurrence for n = 0 to {}: largest relative error {:.1e} ({})",
+            CHECK_TERMS - 1, worst, if worst < 1e-6 { "match" } else { "MISMATCH" }),
+        None => println!("Checked against the recurrence for n = 0 to {}: numbers too large to check", CHECK_TERMS - 1),
+    }
+}
+
+// the exact closed form at n, as a string so it compares with a BigInt term;
+// None when the common denominator does not fit in an i128
+fn exact_value(roots: &[Root], coefficients: &[Fraction], n: usize) -> Option<String> {
+    let common = coefficients.iter().try_fold(1i128, |l, c| (l / gcd(l, c.den)).checked_mul(c.den))?;
+    let mut total = BigInt::from_i64(0);
+    let mut next = coefficients.iter();
+    for root in roots {
+        let r = BigInt::from_i128(root.integer.unwrap());
+        for j in 0..root.multiplicity {
+            let c = next.next().unwrap();
+            let mut term = BigInt::from_i128(c.num.checked_mul(common / c.den)?);
+            if root.integer == Some(0) {
+                if n != j {
+                    continue;
+                }
+            } else {
+                term = &term * &BigInt::from_i128((n as i128).pow(j as u32));
+                for _ in 0..n {
+                    term = &term * &r;
+                }
+            }
+            total = &total + &term;
+        }
+    }
+    // total is a(n) times the common denominator
+    let (quotient, remainder) = total.magnitude.div_rem(&BigUint::from_u128(common as u128));
+    if !remainder.is_zero() {
+        return Some(format!("{} / {}", total, common));
+    }
+    Some(BigInt { negative: total.negative, magnitude: quotient }.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_into_square_free_factors() {
+        // (x - 1)^2 (x^2 + 1)^3 = x^8 - 2x^7 + 4x^6 - 6x^5 + 6x^4 - 6x^3 + 4x^2 - 2x + 1
+        let factors = squarefree_factors(&[1, -2, 4, -6, 6, -6, 4, -2, 1]).unwrap();
+        assert_eq!(factors, vec![(2, vec![1, -1]), (3, vec![1, 0, 1])]);
+    }
+
+    #[test]
+    fn repeated_roots_are_exact() {
+        // (x^2 + 1)^2, from a(n) = -2a(n-2) - a(n-4)
+        let found = roots(&[1, 0, 2, 0, 1]);
+        assert_eq!(found.len(), 2);
+        for r in &found {
+            assert_eq!(r.multiplicity, 2);
+            assert_eq!((r.value.re, r.value.im.abs()), (0.0, 1.0));
+        }
+        assert_eq!(found[0].value.im, -found[1].value.im);
+
+        // (x^2 - 2)^2, from a(n) = 4a(n-2) - 4a(n-4)
+        let found = roots(&[1, 0, -4, 0, 4]);
+        let shown: Vec<String> = found.iter().map(show_root).collect();
+        assert_eq!(shown, ["sqrt(2) = 1.4142135624 (multiplicity 2)", "-sqrt(2) = -1.4142135624 (multiplicity 2)"]);
+    }
+}
@@ -0,0 +1,145 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+// the spigot works in base 10^4, handing out four digits per round
+const BLOCK: u64 = 10_000;
+const BLOCK_DIGITS: usize = 4;
+// series terms kept per block; 14 terms gain a little over four digits
+const TERMS_PER_BLOCK: usize = 14;
+
+/// Rabinowitz–Wagon spigot for pi, in the base-10000 form of Dik Winter's
+/// well-known C program: pi is held as a mixed-radix number in `remainders`,
+/// and each round multiplies it by 10^4 and carries back to the front to
+/// release the next block of digits.
+///
+/// Memory is O(n) in the digits asked for, about 3.5 u32s per digit
+/// (TERMS_PER_BLOCK per four digits), all allocated when the spigot is created;
+/// it does not grow while streaming, and the digits themselves are never stored.
+struct Spigot {
+    remainders: Vec<u32>,
+    terms: usize, // terms still needed; drops by TERMS_PER_BLOCK each round
+    carry: u64,   // remainder left over from the previous round
+}
+
+impl Spigot {
+    fn new(blocks: usize) -> Spigot {
+        let terms = blocks * TERMS_PER_BLOCK;
+        // every term starts as 2 (pi = 2 + 1/3(2 + 2/5(2 + ...))), scaled by 10^4 / 10
+        let mut remainders = vec![(BLOCK / 5) as u32; terms + 1];
+        remainders[terms] = 0;
+        Spigot { remainders, terms, carry: 0 }
+    }
+}
+
+impl Iterator for Spigot {
+    // the next block; it can reach 10^4, carrying into the block before it
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.terms == 0 {
+            return None;
+        }
+        let mut d = 0u64;
+        let mut g = 2 * self.terms as u64;
+        let mut b = self.terms;
+        loop {
+            d += self.remainders[b] as u64 * BLOCK;
+            g -= 1;
+            self.remainders[b] = (d % g) as u32;
+            d /= g;
+            g -= 1;
+            b -= 1;
+            if b == 0 {
+                break;
+            }
+            d *= b as u64;
+        }
+        self.terms -= TERMS_PER_BLOCK;
+        let block = self.carry + d / BLOCK;
+        self.carry = d % BLOCK;
+        Some(block)
+    }
+}
+
+/// Writes digits as they become final, holding back the last block and any
+/// run of 9999 blocks after it until it is known whether a carry comes in
+struct Carrier<'a> {
+    out: &'a mut dyn Write,
+    held: Option<u64>,
+    nines: usize,         // 9999 blocks waiting behind `held`
+    started: bool,        // "3." has been written
+    written: usize,       // decimals written so far
+    wanted: usize,
+    line: Option<usize>,  // break lines after this many decimals
+}
+
+impl Carrier<'_> {
+    fn push(&mut self, block: u64) -> io::Result<()> {
+        if block >= BLOCK {
+            // carry into the held block; the run of nines rolls over to zeros
+            self.emit(self.held.map_or(0, |h| h + 1))?;
+            for _ in 0..self.nines { self.emit(0)?; }
+            self.held = Some(block - BLOCK);
+            self.nines = 0;
+        } else if block == BLOCK - 1 && self.held.is_some() {
+            self.nines += 1;
+        } else {
+            if let Some(h) = self.held { self.emit(h)?; }
+            for _ in 0..self.nines { self.emit(BLOCK - 1)?; }
+            self.held = Some(block);
+            self.nines = 0;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some(h) = self.held.take() { self.emit(h)?; }
+        for _ in 0..self.nines { self.emit(BLOCK - 1)?; }
+        if self.line.is_none_or(|n| !self.written.is_multiple_of(n)) {
+            writeln!(self.out)?;
+        }
+        self.out.flush()
+    }
+
+    fn emit(&mut self, block: u64) -> io::Result<()> {
+        let text = format!("{:0width$}", block, width = BLOCK_DIGITS);
+        let mut digits = text.chars();
+        // the very first block is "3141": the 3 goes before the decimal point
+        if !self.started {
+            write!(self.out, "{}.", digits.next().unwrap())?;
+            self.started = true;
+        }
+        for c in digits {
+            if self.written == self.wanted { break; }
+            write!(self.out, "{}", c)?;
+            self.written += 1;
+            if let Some(n) = self.line && self.written.is_multiple_of(n) {
+                writeln!(self.out)?;
+                self.out.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stream pi to `decimals` places into `out`, reporting digits per second on
+/// stderr about once a second; `line` breaks the output into lines for a terminal.
+/// Returns how long the whole run took.
+pub fn stream(decimals: usize, out: &mut dyn Write, line: Option<usize>) -> io::Result<Duration> {
+    let start = Instant::now();
+    let mut last_report = start;
+    // one block for the leading 3 and two spare so the last digits are settled
+    let blocks = (decimals + 1).div_ceil(BLOCK_DIGITS) + 2;
+    let mut carrier = Carrier { out, held: None, nines: 0, started: false, written: 0, wanted: decimals, line };
+
+    for block in Spigot::new(blocks) {
+        carrier.push(block)?;
+        if last_report.elapsed() >= Duration::from_secs(1) {
+            let secs = start.elapsed().as_secs_f64();
+            eprintln!("[{} of {} digits, {:.0} digits/s]", carrier.written, decimals, carrier.written as f64 / secs);
+            last_report = Instant::now();
+        }
+    }
+    carrier.finish()?;
+    Ok(start.elapsed())
+}